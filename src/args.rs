@@ -1,6 +1,32 @@
+use crate::{
+    game::{
+        attack::AttackTable,
+        garbage::{
+            GarbageMessiness, GarbageStyle, DEFAULT_GARBAGE_CAP, DEFAULT_GARBAGE_DELAY,
+            DEFAULT_GARBAGE_MESSINESS,
+        },
+        gravity::GravityKind,
+        lock_down::{LockDownKind, DEFAULT_LOCK_DOWN_MOVE_LIMIT},
+        randomizer::RandomizerKind,
+        rules::{FieldSize, Rules, DEFAULT_FIELD_HEIGHT, DEFAULT_FIELD_WIDTH},
+    },
+    mino::spin::SpinRule,
+    mode::{
+        dig::{DEFAULT_DIG_ROWS, MIN_DIG_FIELD_HEIGHT},
+        sprint::DEFAULT_SPRINT_LINES,
+        ultra::DEFAULT_ULTRA_MINUTES,
+        GameMode,
+    },
+    rotation::RotationSystemKind,
+};
 use bevy::prelude::*;
-use clap::Parser;
+use clap::{
+    builder::{PossibleValuesParser, TypedValueParser},
+    error::ErrorKind,
+    ArgAction, CommandFactory, Parser,
+};
 use serde::Deserialize;
+use std::{path::PathBuf, time::Duration};
 
 #[derive(Debug, Clone, Parser, Deserialize, Resource)]
 #[clap(name = "Betris")]
@@ -9,4 +35,189 @@ pub struct Args {
     pub matchbox: String,
     #[clap(short, long, default_value = "1")]
     pub players: usize,
+    // matchboxに接続せずに1人で遊ぶ
+    #[clap(long)]
+    pub offline: bool,
+    // versus以外は通信せずに1人で遊ぶ
+    #[clap(long, value_enum, default_value = "versus")]
+    pub mode: GameMode,
+    // sprintで消すライン数
+    #[clap(
+        long,
+        default_value_t = DEFAULT_SPRINT_LINES,
+        value_parser = PossibleValuesParser::new(["20", "40", "100"]).map(|lines| lines.parse::<u32>().unwrap()),
+    )]
+    pub sprint_lines: u32,
+    // ultraの制限時間(分)
+    #[clap(
+        long,
+        default_value_t = DEFAULT_ULTRA_MINUTES,
+        value_parser = PossibleValuesParser::new(["2", "3"]).map(|minutes| minutes.parse::<u64>().unwrap()),
+    )]
+    pub ultra_minutes: u64,
+    // marathonをライン数で終わらせず，ゲームオーバーまで続ける
+    #[clap(long)]
+    pub marathon_endless: bool,
+    // digで最初に積まれているおじゃま行の数．フィールドの高さに収まるように減らす
+    #[clap(long, default_value_t = DEFAULT_DIG_ROWS, value_parser = clap::value_parser!(u8).range(1..))]
+    pub dig_rows: u8,
+    // digで全てのおじゃま行を消すまでではなく，一定時間ごとにおじゃま行を増やしてゲームオーバーまで続ける
+    #[clap(long)]
+    pub dig_survival: bool,
+    // 1人用のモードの自己ベストを保存するファイル
+    #[clap(long, default_value = "records.ron")]
+    pub records: PathBuf,
+    // ホストの場合のみ使われる．指定しない場合はランダム
+    #[clap(long)]
+    pub seed: Option<u64>,
+    #[clap(long, default_value = "true", action = ArgAction::Set)]
+    pub ghost: bool,
+    #[clap(long, default_value = "0.3", value_parser = parse_opacity)]
+    pub ghost_opacity: f32,
+    // 操作の無駄があった場合に警告を表示する．1人で練習する場合のみ有効
+    #[clap(long)]
+    pub finesse: bool,
+    // 以下はホストの場合のみ使われる試合のルール
+    #[clap(long, value_enum, default_value = "srs")]
+    pub rotation_system: RotationSystemKind,
+    #[clap(long, value_enum, default_value = "7-bag")]
+    pub randomizer: RandomizerKind,
+    #[clap(long, value_enum, default_value = "extended")]
+    pub lock_down: LockDownKind,
+    // 延長できる回数．extendedの場合のみ使われる
+    #[clap(long, default_value_t = DEFAULT_LOCK_DOWN_MOVE_LIMIT)]
+    pub lock_down_limit: u8,
+    #[clap(long, value_enum, default_value = "fixed")]
+    pub gravity: GravityKind,
+    // 20以上で20G
+    #[clap(long, default_value = "1")]
+    pub start_level: u32,
+    // guideline，tetris99，tetrioのいずれかか，RONファイルのパス
+    #[clap(long, default_value = "guideline", value_parser = AttackTable::load)]
+    pub attack_table: AttackTable,
+    #[clap(long, default_value = "true", action = ArgAction::Set)]
+    pub garbage_cancel: bool,
+    // ミリ秒
+    #[clap(long, default_value_t = DEFAULT_GARBAGE_DELAY.as_millis() as u64)]
+    pub garbage_delay: u64,
+    // 1回の設置でせり上がるおじゃま行の上限．0では全くせり上がらなくなるため1以上
+    #[clap(long, default_value_t = DEFAULT_GARBAGE_CAP, value_parser = clap::value_parser!(u8).range(1..))]
+    pub garbage_cap: u8,
+    #[clap(long, value_enum, default_value = "messy")]
+    pub garbage_style: GarbageStyle,
+    // 以下はmessyの場合のみ使われる，穴の列が変わる確率
+    // 攻撃の最初の行
+    #[clap(long, default_value_t = DEFAULT_GARBAGE_MESSINESS.attack)]
+    pub garbage_attack_messiness: f64,
+    // 攻撃の2行目以降
+    #[clap(long, default_value_t = DEFAULT_GARBAGE_MESSINESS.row)]
+    pub garbage_row_messiness: f64,
+    #[clap(long, value_enum, default_value = "t-spin")]
+    pub spin_rule: SpinRule,
+    // 4列の場合は4-wideの練習になる
+    #[clap(long, default_value_t = DEFAULT_FIELD_WIDTH, value_parser = clap::value_parser!(i8).range(4..=40))]
+    pub field_width: i8,
+    // 見えている部分の高さ
+    #[clap(long, default_value_t = DEFAULT_FIELD_HEIGHT, value_parser = clap::value_parser!(i8).range(4..=40))]
+    pub field_height: i8,
+}
+
+impl Args {
+    // 1つの引数では決まらない制約を調べる
+    pub fn validate(&self) -> Result<(), clap::Error> {
+        if self.mode == GameMode::Dig && self.field_height < MIN_DIG_FIELD_HEIGHT {
+            return Err(Self::command().error(
+                ErrorKind::ArgumentConflict,
+                format!("--field-height must be at least {MIN_DIG_FIELD_HEIGHT} for dig"),
+            ));
+        }
+
+        Ok(())
+    }
+
+    // versus以外のモードは常に通信しない
+    pub fn is_offline(&self) -> bool {
+        self.offline || self.mode.is_solo()
+    }
+
+    pub fn rules(&self) -> Rules {
+        // marathonは消したライン数に応じて速くなる
+        let gravity = if self.mode == GameMode::Marathon {
+            GravityKind::Level
+        } else {
+            self.gravity
+        };
+        // 穴の無いおじゃま行は掘れないため，digでは行ごとに穴を開ける
+        let garbage_style =
+            if self.mode == GameMode::Dig && self.garbage_style == GarbageStyle::Solid {
+                GarbageStyle::Cheese
+            } else {
+                self.garbage_style
+            };
+
+        Rules {
+            rotation_system: self.rotation_system,
+            randomizer: self.randomizer,
+            lock_down: self.lock_down,
+            lock_down_limit: self.lock_down_limit,
+            gravity,
+            start_level: self.start_level,
+            attack_table: self.attack_table.clone(),
+            garbage_cancel: self.garbage_cancel,
+            garbage_delay: Duration::from_millis(self.garbage_delay),
+            garbage_cap: self.garbage_cap,
+            garbage_style,
+            garbage_messiness: GarbageMessiness {
+                attack: self.garbage_attack_messiness,
+                row: self.garbage_row_messiness,
+            },
+            spin_rule: self.spin_rule,
+            field_size: FieldSize {
+                width: self.field_width,
+                height: self.field_height,
+            },
+        }
+    }
+}
+
+// 0.0から1.0までの不透明度．NaNは範囲外として扱う
+fn parse_opacity(value: &str) -> Result<f32, String> {
+    let opacity = value.parse::<f32>().map_err(|err| err.to_string())?;
+    if (0.0..=1.0).contains(&opacity) {
+        Ok(opacity)
+    } else {
+        Err(format!("{opacity} is not in 0.0..=1.0"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dig_needs_room_for_garbage() {
+        let args =
+            Args::try_parse_from(["betris", "--mode", "dig", "--field-height", "4"]).unwrap();
+        assert!(args.validate().is_err());
+
+        let args =
+            Args::try_parse_from(["betris", "--mode", "dig", "--field-height", "5"]).unwrap();
+        assert!(args.validate().is_ok());
+
+        // dig以外は低いフィールドでも遊べる
+        let args = Args::try_parse_from(["betris", "--field-height", "4"]).unwrap();
+        assert!(args.validate().is_ok());
+    }
+
+    #[test]
+    fn ghost_opacity_must_be_in_range() {
+        let parse =
+            |opacity| Args::try_parse_from(["betris", &format!("--ghost-opacity={opacity}")]);
+        for opacity in ["0", "0.5", "1.0"] {
+            assert!(parse(opacity).is_ok());
+        }
+        for opacity in ["-0.1", "1.1", "NaN", "inf"] {
+            assert!(parse(opacity).is_err());
+        }
+    }
 }
@@ -0,0 +1,131 @@
+use super::Field;
+use crate::{game::ClearType, mino::spin::Spin, net::PlayerId};
+use bevy::{prelude::*, sprite::Anchor};
+use std::time::Duration;
+
+static ACTION_TEXT_SIZE: f32 = 28.0;
+static ACTION_TEXT_COLOR: Color = Color::rgb(0.8, 0.2, 0.0);
+// フィールドの左側，おじゃま行の警告の外に表示する
+static ACTION_TEXT_OFFSET_X: f32 = -40.0;
+static ACTION_TEXT_Y: f32 = -60.0;
+// 表示している間に上に動く距離
+static ACTION_TEXT_RISE: f32 = 30.0;
+const ACTION_TEXT_DURATION: Duration = Duration::from_millis(1500);
+
+#[derive(Debug, Event)]
+pub struct ClearEvent {
+    pub player_id: PlayerId,
+    pub clear_type: ClearType,
+}
+
+#[derive(Component)]
+pub struct ActionText(Timer);
+
+pub fn spawn_action_text_system(
+    mut commands: Commands,
+    mut clear_events: EventReader<ClearEvent>,
+    field_query: Query<(Entity, &Field)>,
+    action_text_query: Query<(Entity, &Parent), With<ActionText>>,
+) {
+    for event in clear_events.read() {
+        let Some((field_entity, field)) = field_query
+            .iter()
+            .find(|(_, field)| field.player.id == event.player_id)
+        else {
+            continue;
+        };
+
+        // 前の表示は新しい表示で置き換える
+        for (entity, parent) in &action_text_query {
+            if parent.get() == field_entity {
+                commands.entity(entity).despawn_recursive();
+            }
+        }
+
+        commands.entity(field_entity).with_children(|parent| {
+            parent.spawn((
+                ActionText(Timer::new(ACTION_TEXT_DURATION, TimerMode::Once)),
+                Text2dBundle {
+                    transform: Transform::from_translation(Vec3::new(
+                        -field.size().pixel_width() / 2.0 + ACTION_TEXT_OFFSET_X,
+                        ACTION_TEXT_Y,
+                        1.0,
+                    )),
+                    text: Text::from_section(
+                        action_text(event.clear_type),
+                        TextStyle {
+                            font_size: ACTION_TEXT_SIZE,
+                            color: ACTION_TEXT_COLOR,
+                            ..default()
+                        },
+                    )
+                    .with_justify(JustifyText::Right),
+                    text_anchor: Anchor::CenterRight,
+                    ..default()
+                },
+            ));
+        });
+    }
+}
+
+// 上に動きながら，後半で消えていく
+pub fn action_text_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut action_text_query: Query<(Entity, &mut ActionText, &mut Transform, &mut Text)>,
+) {
+    for (entity, mut action_text, mut transform, mut text) in &mut action_text_query {
+        if action_text.0.tick(time.delta()).finished() {
+            commands.entity(entity).despawn_recursive();
+            continue;
+        }
+
+        let progress = action_text.0.fraction();
+        transform.translation.y = ACTION_TEXT_Y + ACTION_TEXT_RISE * progress;
+        let alpha = (2.0 - progress * 2.0).min(1.0);
+        for section in &mut text.sections {
+            section.style.color.set_a(alpha);
+        }
+    }
+}
+
+// 例: "BACK-TO-BACK\nT-SPIN DOUBLE\n3 REN"
+fn action_text(clear_type: ClearType) -> String {
+    let mut lines = Vec::new();
+
+    if clear_type.is_back_to_back {
+        lines.push("BACK-TO-BACK".to_string());
+    }
+
+    let clear_name = match clear_type.lines {
+        0 => "",
+        1 => "SINGLE",
+        2 => "DOUBLE",
+        3 => "TRIPLE",
+        _ => "TETRIS",
+    };
+    let spin_name = match clear_type.spin {
+        Spin::None => String::new(),
+        Spin::Mini(shape) => format!("{shape:?}-SPIN MINI"),
+        Spin::Full(shape) => format!("{shape:?}-SPIN"),
+    };
+    let action = [spin_name.as_str(), clear_name]
+        .into_iter()
+        .filter(|name| !name.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ");
+    if !action.is_empty() {
+        lines.push(action);
+    }
+
+    // 2回目の連続消去から1 REN
+    if clear_type.combo >= 2 {
+        lines.push(format!("{} REN", clear_type.combo - 1));
+    }
+
+    if clear_type.is_perfect_clear {
+        lines.push("ALL CLEAR".to_string());
+    }
+
+    lines.join("\n")
+}
@@ -1,20 +1,19 @@
 use bevy::{prelude::*, sprite::Anchor};
 
-use crate::{
-    mino::{shape::Shape, Mino},
-    position::Position,
-};
+use crate::{args::Args, game::rules::FieldSize, mino::shape::Shape, position::Position};
 
 pub const BLOCK_SIZE: f32 = 30.0;
 pub const BLOCK_INSET: f32 = 1.0;
 
-use super::Field;
+use super::{local::LocalField, Field};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Component)]
 pub enum Block {
     #[default]
     Empty,
     Garbage,
+    // 消せないおじゃま行
+    Solid,
     I,
     O,
     T,
@@ -36,6 +35,7 @@ impl Block {
             Block::T => Color::rgb(0.5, 0.0, 1.0),
             Block::Z => Color::rgb(1.0, 0.0, 0.0),
             Block::Garbage => Color::rgb(0.5, 0.5, 0.5),
+            Block::Solid => Color::rgb(0.3, 0.3, 0.3),
         }
     }
 
@@ -46,6 +46,11 @@ impl Block {
     pub fn is_filled(&self) -> bool {
         !self.is_empty()
     }
+
+    // 埋まっていても消せないブロックがある
+    pub fn is_clearable(&self) -> bool {
+        self.is_filled() && self != &Self::Solid
+    }
 }
 
 impl From<Shape> for Block {
@@ -65,49 +70,68 @@ impl From<Shape> for Block {
 #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
 pub fn field_block_system(
     mut commands: Commands,
+    args: Res<Args>,
     field_block_query: Query<Entity, With<Block>>,
-    field_query: Query<(Entity, &Field)>,
-    mino_query: Query<(Entity, &Mino)>,
+    field_query: Query<(Entity, &Field, Option<&LocalField>)>,
 ) {
     for block_entity in field_block_query.iter() {
         commands.entity(block_entity).despawn_recursive();
     }
 
-    for (field_entity, field) in field_query.iter() {
-        let field_block_bundles = field
+    for (field_entity, field, local_field) in field_query.iter() {
+        let size = field.size();
+        let mut bundles = field
             .blocks
             .indexed_iter()
             .filter(|(_, block)| !block.is_empty())
-            .map(move |(pos, &block)| create_field_block_bundle(pos, block))
+            .map(|(pos, &block)| create_field_block_bundle(pos, block, size))
             .collect::<Vec<_>>();
 
-        commands.entity(field_entity).with_children(|parent| {
-            for bundle in field_block_bundles {
-                parent.spawn(bundle);
+        if let Some(mino) = local_field.and_then(|local_field| local_field.game.mino) {
+            let mino_positions = mino.positions().collect::<Vec<_>>();
+
+            if let Some(ghost) = local_field
+                .and_then(|local_field| local_field.game.ghost_mino())
+                .filter(|_| args.ghost)
+            {
+                bundles.extend(
+                    ghost
+                        .positions()
+                        // 操作中のミノと重なる部分は表示しない
+                        .filter(|pos| !mino_positions.contains(pos))
+                        .map(|pos| {
+                            create_ghost_block_bundle(
+                                pos,
+                                ghost.shape.into(),
+                                size,
+                                args.ghost_opacity,
+                            )
+                        }),
+                );
             }
-        });
-    }
 
-    for (mino_entity, mino) in mino_query.iter() {
-        let mino_block_bundles = mino
-            .shape
-            .blocks(mino.angle)
-            .iter()
-            .map(|&pos| pos + mino.pos)
-            .map(|pos| create_field_block_bundle(pos, mino.shape.into()))
-            .collect::<Vec<_>>();
+            bundles.extend(
+                mino_positions
+                    .iter()
+                    .map(|&pos| create_field_block_bundle(pos, mino.shape.into(), size)),
+            );
+        }
 
-        commands.entity(mino_entity).with_children(|parent| {
-            for bundle in mino_block_bundles {
+        commands.entity(field_entity).with_children(|parent| {
+            for bundle in bundles {
                 parent.spawn(bundle);
             }
         });
     }
 }
 
-fn create_field_block_bundle(pos: Position, block: Block) -> (SpriteBundle, Block) {
+fn create_field_block_bundle(
+    pos: Position,
+    block: Block,
+    size: FieldSize,
+) -> (SpriteBundle, Block) {
     let bundle = SpriteBundle {
-        transform: Transform::from_translation(pos.translation()),
+        transform: Transform::from_translation(pos.translation(size)),
         sprite: Sprite {
             anchor: Anchor::BottomLeft,
             color: block.color(),
@@ -122,3 +146,15 @@ fn create_field_block_bundle(pos: Position, block: Block) -> (SpriteBundle, Bloc
 
     (bundle, block)
 }
+
+fn create_ghost_block_bundle(
+    pos: Position,
+    block: Block,
+    size: FieldSize,
+    opacity: f32,
+) -> (SpriteBundle, Block) {
+    let (mut bundle, block) = create_field_block_bundle(pos, block, size);
+    bundle.sprite.color.set_a(opacity);
+
+    (bundle, block)
+}
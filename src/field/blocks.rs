@@ -1,24 +1,34 @@
-use super::{block::Block, FIELD_MAX_HEIGHT, FIELD_WIDTH};
+use super::block::Block;
 use crate::{
-    mino::{shape::Shape, Angle, Mino},
+    game::{
+        garbage::{GarbageMessiness, GarbageStyle},
+        rules::FieldSize,
+        TopOut,
+    },
+    mino::Mino,
     pos,
     position::Position,
 };
 use rand::prelude::*;
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Copy)]
-pub struct Blocks([[Block; FIELD_WIDTH as usize]; FIELD_MAX_HEIGHT as usize]);
+// 下の行から順に並べたフィールドのブロック
+#[derive(Debug, Clone)]
+pub struct Blocks {
+    size: FieldSize,
+    lines: Vec<Vec<Block>>,
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Lines(Vec<u8>);
 
+// 各行の穴の列．Noneは穴が無く消せない行
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct Garbages(Vec<u8>);
+pub struct Garbages(Vec<Option<u8>>);
 
 impl Default for Blocks {
     fn default() -> Self {
-        Self([[Block::default(); FIELD_WIDTH as usize]; FIELD_MAX_HEIGHT as usize])
+        Self::new(FieldSize::default())
     }
 }
 
@@ -28,40 +38,46 @@ impl Default for Blocks {
     clippy::cast_possible_wrap
 )]
 impl Blocks {
+    pub fn new(size: FieldSize) -> Self {
+        Self {
+            size,
+            lines: vec![Self::empty_line(size); size.max_height() as usize],
+        }
+    }
+
+    pub fn size(&self) -> FieldSize {
+        self.size
+    }
+
     pub fn get(&self, pos: Position) -> Option<&Block> {
-        if Self::check_pos(pos) {
-            self.0.get(pos.y as usize)?.get(pos.x as usize)
+        if self.check_pos(pos) {
+            self.lines.get(pos.y as usize)?.get(pos.x as usize)
         } else {
             None
         }
     }
     pub fn get_mut(&mut self, pos: Position) -> Option<&mut Block> {
-        if Self::check_pos(pos) {
-            self.0.get_mut(pos.y as usize)?.get_mut(pos.x as usize)
+        if self.check_pos(pos) {
+            self.lines.get_mut(pos.y as usize)?.get_mut(pos.x as usize)
         } else {
             None
         }
     }
 
-    pub fn can_place_mino(&self, mino_pos: Position, shape: Shape, angle: Angle) -> bool {
-        shape
-            .blocks(angle)
-            .iter()
-            .map(|&pos| pos + mino_pos)
-            .all(|pos| self.get(pos).map_or(false, Block::is_empty))
+    pub fn can_place_mino(&self, mino: &Mino) -> bool {
+        mino.positions()
+            .all(|pos| self.get(pos).is_some_and(Block::is_empty))
     }
 
     pub fn place_mino(&mut self, mino: &Mino) {
-        for &block_pos in mino.shape.blocks(mino.angle) {
-            let pos = block_pos + mino.pos;
-
+        for pos in mino.positions() {
             let block = self.get_mut(pos).unwrap();
             *block = mino.shape.into();
         }
     }
 
     pub fn indexed_iter(&self) -> impl Iterator<Item = (Position, &Block)> {
-        self.0.iter().enumerate().flat_map(|(y, line)| {
+        self.lines.iter().enumerate().flat_map(|(y, line)| {
             line.iter()
                 .enumerate()
                 .map(move |(x, block)| (pos!(x as i8, y as i8), block))
@@ -70,10 +86,10 @@ impl Blocks {
 
     pub fn get_filled_lines(&self) -> Lines {
         let full_filled_lines = self
-            .0
+            .lines
             .iter()
             .enumerate()
-            .filter(|(_, line)| line.iter().all(Block::is_filled))
+            .filter(|(_, line)| line.iter().all(Block::is_clearable))
             .map(|(y, _)| y as u8)
             .rev()
             .collect::<Vec<_>>();
@@ -81,50 +97,78 @@ impl Blocks {
         Lines(full_filled_lines)
     }
 
+    // 消せるおじゃま行が残っている行の数
+    pub fn count_garbage_rows(&self) -> usize {
+        self.lines
+            .iter()
+            .filter(|line| line.contains(&Block::Garbage))
+            .count()
+    }
+
+    // おじゃま行を含むラインの数
+    pub fn count_garbage_lines(&self, lines: &Lines) -> usize {
+        lines
+            .0
+            .iter()
+            .filter(|&&y| self.lines[y as usize].contains(&Block::Garbage))
+            .count()
+    }
+
     pub fn clear_lines(&mut self, full_filled_lines: &Lines) {
+        // 上の行から消すため，消していない行の位置はずれない
         for &clear_y in &full_filled_lines.0 {
-            for y in clear_y..(FIELD_MAX_HEIGHT as u8 - 1) {
-                self.0[y as usize] = self.0[(y + 1) as usize];
-            }
-            self.0[(FIELD_MAX_HEIGHT - 1) as usize] = [Block::default(); FIELD_WIDTH as usize];
+            self.lines.remove(clear_y as usize);
+            self.lines.push(Self::empty_line(self.size));
         }
     }
 
-    #[allow(clippy::result_unit_err)]
-    pub fn add_garbages(&mut self, garbages: &Garbages) -> Result<(), ()> {
-        if self.is_gameorver(garbages) {
-            return Err(());
-        }
-
-        for y in (0..(FIELD_MAX_HEIGHT as usize - 1 - garbages.len())).rev() {
-            self.0[y + garbages.len()] = self.0[y];
+    pub fn add_garbages(&mut self, garbages: &Garbages) -> Result<(), TopOut> {
+        if self.is_garbage_out(garbages) {
+            return Err(TopOut::GarbageOut);
         }
 
-        for (y, hole_x) in garbages.0.iter().rev().enumerate() {
-            for x in 0..(FIELD_WIDTH as usize) {
-                self.0[y][x] = if x == *hole_x as usize {
-                    Block::Empty
-                } else {
-                    Block::Garbage
-                };
-            }
+        self.lines.truncate(self.lines.len() - garbages.len());
+        for hole_x in &garbages.0 {
+            let line = (0..self.size.width as usize)
+                .map(|x| match hole_x {
+                    Some(hole_x) if x == *hole_x as usize => Block::Empty,
+                    Some(_) => Block::Garbage,
+                    None => Block::Solid,
+                })
+                .collect();
+            self.lines.insert(0, line);
         }
 
         Ok(())
     }
 
-    fn is_gameorver(&self, garbages: &Garbages) -> bool {
-        !self.0[(FIELD_MAX_HEIGHT as usize - garbages.len())..(FIELD_MAX_HEIGHT as usize)]
+    fn is_garbage_out(&self, garbages: &Garbages) -> bool {
+        garbages.len() > self.lines.len()
+            || !self.lines[(self.lines.len() - garbages.len())..]
+                .iter()
+                .all(|line| line.iter().all(Block::is_empty))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lines
             .iter()
             .all(|line| line.iter().all(Block::is_empty))
     }
 
-    pub fn is_empty(&self) -> bool {
-        self.0.iter().all(|line| line.iter().all(Block::is_empty))
+    // ブロックが積まれている高さ
+    pub fn height(&self) -> usize {
+        self.lines
+            .iter()
+            .rposition(|line| line.iter().any(Block::is_filled))
+            .map_or(0, |y| y + 1)
     }
 
-    pub(crate) fn check_pos(pos: Position) -> bool {
-        0 <= pos.x && pos.x < FIELD_WIDTH && 0 <= pos.y && pos.y < FIELD_MAX_HEIGHT
+    pub(crate) fn check_pos(&self, pos: Position) -> bool {
+        0 <= pos.x && pos.x < self.size.width && 0 <= pos.y && pos.y < self.size.max_height()
+    }
+
+    fn empty_line(size: FieldSize) -> Vec<Block> {
+        vec![Block::default(); size.width as usize]
     }
 }
 
@@ -139,26 +183,40 @@ impl Lines {
 }
 
 impl Garbages {
-    pub fn from_amount(amount: u8) -> Self {
-        // 一度のおじゃま送信では70%の確率で同じ列に穴が出来る
-        let vec = (0..amount)
-            .scan(None, |prev, _| match *prev {
-                None => {
-                    *prev = Some(get_random_x());
-                    *prev
-                }
-                Some(x) => {
-                    if rand::thread_rng().gen_bool(0.7) {
-                        Some(x)
-                    } else {
-                        *prev = Some(get_random_x());
-                        *prev
-                    }
-                }
-            })
-            .collect();
+    // 攻撃ごとのおじゃま行数から穴の位置を決める
+    // どの穴の開け方でも試合のシードから生成した乱数だけを使うため，同じ試合では同じおじゃま行になる
+    pub fn generate(
+        amounts: &[u8],
+        width: i8,
+        style: GarbageStyle,
+        messiness: GarbageMessiness,
+        rng: &mut impl Rng,
+    ) -> Self {
+        let Some(messiness) = style.messiness(messiness) else {
+            let amount = amounts.iter().map(|&amount| amount as usize).sum();
+            return Self(vec![None; amount]);
+        };
+
+        let mut holes = Vec::new();
+        let mut prev = None;
+        for &amount in amounts {
+            for row in 0..amount {
+                let probability = if row == 0 {
+                    messiness.attack
+                } else {
+                    messiness.row
+                };
+                let hole_x = match prev {
+                    Some(x) if !rng.gen_bool(probability.clamp(0.0, 1.0)) => x,
+                    _ => get_random_x(width, rng),
+                };
+
+                prev = Some(hole_x);
+                holes.push(Some(hole_x));
+            }
+        }
 
-        Self(vec)
+        Self(holes)
     }
 
     pub fn len(&self) -> usize {
@@ -170,6 +228,95 @@ impl Garbages {
     }
 }
 
-pub(crate) fn get_random_x() -> u8 {
-    rand::thread_rng().gen_range(0..(FIELD_WIDTH as u8))
+#[allow(clippy::cast_sign_loss)]
+pub(crate) fn get_random_x(width: i8, rng: &mut impl Rng) -> u8 {
+    rng.gen_range(0..(width as u8))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::garbage::DEFAULT_GARBAGE_MESSINESS;
+    use rand_chacha::ChaCha8Rng;
+
+    const WIDTH: i8 = 10;
+
+    fn generate(amounts: &[u8], style: GarbageStyle) -> Garbages {
+        let mut rng = ChaCha8Rng::seed_from_u64(0);
+        Garbages::generate(amounts, WIDTH, style, DEFAULT_GARBAGE_MESSINESS, &mut rng)
+    }
+
+    // 攻撃ごとに分けた穴の列
+    fn holes_per_attack(garbages: &Garbages, amounts: &[u8]) -> Vec<Vec<u8>> {
+        let mut holes = garbages.0.iter();
+        amounts
+            .iter()
+            .map(|&amount| {
+                holes
+                    .by_ref()
+                    .take(amount.into())
+                    .map(|hole| hole.unwrap())
+                    .collect()
+            })
+            .collect()
+    }
+
+    fn count_hole_changes(holes: &[u8]) -> usize {
+        holes.windows(2).filter(|pair| pair[0] != pair[1]).count()
+    }
+
+    #[test]
+    fn solid_has_no_holes() {
+        let garbages = generate(&[2, 3], GarbageStyle::Solid);
+        assert_eq!(garbages.len(), 5);
+        assert!(garbages.0.iter().all(Option::is_none));
+    }
+
+    #[test]
+    fn holed_styles_stay_inside_field() {
+        for style in [
+            GarbageStyle::Messy,
+            GarbageStyle::Clean,
+            GarbageStyle::Cheese,
+        ] {
+            let garbages = generate(&[4; 50], style);
+            assert_eq!(garbages.len(), 200);
+            assert!(garbages
+                .0
+                .iter()
+                .all(|hole| hole.is_some_and(|x| i8::try_from(x).unwrap() < WIDTH)));
+        }
+    }
+
+    #[test]
+    fn clean_keeps_column_within_attack() {
+        let amounts = [4; 50];
+        let garbages = generate(&amounts, GarbageStyle::Clean);
+        let attacks = holes_per_attack(&garbages, &amounts);
+
+        assert!(attacks.iter().all(|holes| count_hole_changes(holes) == 0));
+        // 攻撃が変わると穴の列も選び直す
+        let firsts = attacks.iter().map(|holes| holes[0]).collect::<Vec<_>>();
+        assert!(count_hole_changes(&firsts) > 0);
+    }
+
+    #[test]
+    fn cheese_moves_column_every_row() {
+        let garbages = generate(&[100], GarbageStyle::Cheese);
+        let holes = holes_per_attack(&garbages, &[100]).remove(0);
+
+        // 同じ列を選び直す確率は1/10
+        let changes = count_hole_changes(&holes);
+        assert!((80..=99).contains(&changes), "{changes} changes");
+    }
+
+    #[test]
+    fn messy_moves_column_by_row_messiness() {
+        let garbages = generate(&[200], GarbageStyle::Messy);
+        let holes = holes_per_attack(&garbages, &[200]).remove(0);
+
+        // 0.3の確率で選び直し，そのうち1/10は同じ列になる
+        let changes = count_hole_changes(&holes);
+        assert!((35..=75).contains(&changes), "{changes} changes");
+    }
 }
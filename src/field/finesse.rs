@@ -0,0 +1,90 @@
+use super::{blocks::Blocks, Field};
+use crate::{args::Args, game::finesse::min_key_count, mino::Mino};
+use bevy::prelude::*;
+use std::time::Duration;
+
+static FINESSE_WARNING_COLOR: Color = Color::rgba(1.0, 0.0, 0.0, 0.3);
+const FINESSE_WARNING_DURATION: Duration = Duration::from_millis(300);
+
+// 練習の場合のみ自分のフィールドに追加する
+#[derive(Component, Default)]
+pub struct FinesseChecker {
+    // 今のミノを操作するのに押したキーの回数
+    keys: u32,
+    is_held: bool,
+}
+
+#[derive(Event)]
+pub struct FinesseFaultEvent;
+
+// 操作に無駄があった場合にフィールドを点滅させる
+#[derive(Component)]
+pub struct FinesseWarning(Timer);
+
+impl FinesseChecker {
+    pub fn add_key(&mut self) {
+        self.keys += 1;
+    }
+
+    pub fn hold(&mut self) {
+        self.is_held = true;
+    }
+
+    // 設置する前のフィールドで，設置したミノの操作に無駄があったか
+    // ホールドした場合や，出現位置からハードドロップで届かない位置の場合は調べずにNone
+    pub fn check(&mut self, placed: &Mino, blocks: &Blocks) -> Option<bool> {
+        let keys = self.keys;
+        let is_held = self.is_held;
+        self.keys = 0;
+        self.is_held = false;
+
+        if is_held {
+            return None;
+        }
+
+        min_key_count(placed, blocks).map(|min_keys| keys > min_keys)
+    }
+}
+
+pub fn finesse_warning_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    args: Res<Args>,
+    mut fault_events: EventReader<FinesseFaultEvent>,
+    field_query: Query<(Entity, &Field), With<FinesseChecker>>,
+    mut warning_query: Query<(Entity, &mut FinesseWarning, &mut Sprite)>,
+) {
+    for (entity, mut warning, mut sprite) in &mut warning_query {
+        if warning.0.tick(time.delta()).finished() {
+            commands.entity(entity).despawn_recursive();
+        } else {
+            let alpha = FINESSE_WARNING_COLOR.a() * (1.0 - warning.0.fraction());
+            sprite.color.set_a(alpha);
+        }
+    }
+
+    if fault_events.read().count() == 0 || !args.finesse {
+        return;
+    }
+    let Ok((field_entity, field)) = field_query.get_single() else {
+        return;
+    };
+
+    commands.entity(field_entity).with_children(|parent| {
+        parent.spawn((
+            FinesseWarning(Timer::new(FINESSE_WARNING_DURATION, TimerMode::Once)),
+            SpriteBundle {
+                transform: Transform::from_translation(Vec3::new(0.0, 0.0, 0.5)),
+                sprite: Sprite {
+                    color: FINESSE_WARNING_COLOR,
+                    custom_size: Some(Vec2::new(
+                        field.size().pixel_width(),
+                        field.size().pixel_height(),
+                    )),
+                    ..default()
+                },
+                ..default()
+            },
+        ));
+    });
+}
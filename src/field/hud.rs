@@ -0,0 +1,55 @@
+use super::local::{hold_pos, LocalField, NEXT_HOLD_BG_HEIGHT, NEXT_HOLD_BG_PADDING};
+use crate::game::rules::FieldSize;
+use bevy::{prelude::*, sprite::Anchor};
+
+static HUD_TEXT_SIZE: f32 = 24.0;
+static HUD_TEXT_COLOR: Color = Color::rgb(0.2, 0.2, 0.2);
+// ホールドの下に表示する
+static HUD_OFFSET_Y: f32 = -NEXT_HOLD_BG_HEIGHT / 2.0 - NEXT_HOLD_BG_PADDING * 2.0;
+
+// 得点，レベル，消したライン数
+#[derive(Component)]
+pub struct Hud;
+
+impl Hud {
+    pub fn spawn(parent: &mut ChildBuilder, size: FieldSize) {
+        parent.spawn((
+            Hud,
+            Text2dBundle {
+                transform: Transform::from_translation(
+                    hold_pos(size) + Vec3::new(0.0, HUD_OFFSET_Y, 1.0),
+                ),
+                text: Text::from_section(
+                    "",
+                    TextStyle {
+                        font_size: HUD_TEXT_SIZE,
+                        color: HUD_TEXT_COLOR,
+                        ..default()
+                    },
+                )
+                .with_justify(JustifyText::Center),
+                text_anchor: Anchor::TopCenter,
+                ..default()
+            },
+        ));
+    }
+}
+
+pub fn hud_system(
+    mut hud_query: Query<(&mut Text, &Parent), With<Hud>>,
+    local_field_query: Query<&LocalField>,
+) {
+    for (mut text, parent) in &mut hud_query {
+        let Ok(local_field) = local_field_query.get(parent.get()) else {
+            continue;
+        };
+        let game = &local_field.game;
+
+        text.sections[0].value = format!(
+            "SCORE\n{}\nLEVEL\n{}\nLINES\n{}",
+            game.score.points,
+            game.gravity.level(),
+            game.score.lines,
+        );
+    }
+}
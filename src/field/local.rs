@@ -1,181 +1,301 @@
 use super::{
+    action::ClearEvent,
     block::{BLOCK_INSET, BLOCK_SIZE},
-    next::{NextQueue, QUEUE_SIZE},
-    timer::{DropTimer, LockDownTimer, TargetChangeTimer},
-    FIELD_BACKGROUND_COLOR, FIELD_PIXEL_HEIGHT, FIELD_PIXEL_WIDTH,
+    finesse::{FinesseChecker, FinesseFaultEvent},
+    next::QUEUE_SIZE,
+    stats::Stats,
+    target::{TargetChangeTimer, TargetingStrategy},
+    Field, FIELD_BACKGROUND_COLOR,
 };
 use crate::{
-    mino::{event::SpawnMinoEvent, shape::Shape, t_spin::TSpin, Angle, Mino},
-    net::PlayerId,
+    game::{
+        rules::{FieldSize, MatchSettings},
+        GameEvent, GameState, Input,
+    },
+    input::{KeyPressEvent, MOVE_KEYS},
+    movement::MoveEvent,
+    net::{send_garbage, sync_local_field_change, PlayerId, Players, Socket},
     position::Position,
+    rotation::bottom,
+    state::GameOverEvent,
 };
 use bevy::{prelude::*, sprite::Anchor};
 
 static GARBAGE_WARN_BAR_COLOR: Color = Color::rgb(1.0, 0.0, 0.0);
+static GARBAGE_WARN_BAR_QUEUED_COLOR: Color = Color::rgb(1.0, 0.6, 0.6);
 static GARBAGE_WARN_BAR_WIDTH: f32 = 20.0;
 static GARBAGE_WARN_BAR_INSET: f32 = 4.0;
-static GARBAGE_WARN_BAR_START_X: f32 =
-    -FIELD_PIXEL_WIDTH / 2.0 - GARBAGE_WARN_BAR_WIDTH / 2.0 - GARBAGE_WARN_BAR_INSET;
-static GARBAGE_WARN_BAR_START_Y: f32 = -FIELD_PIXEL_HEIGHT / 2.0;
 
 pub const NEXT_HOLD_BLOCK_SIZE: f32 = BLOCK_SIZE * 0.6;
 pub const NEXT_HOLD_BLOCK_INSET: f32 = BLOCK_INSET * 0.6;
 pub const NEXT_HOLD_BG_PADDING: f32 = NEXT_HOLD_BLOCK_SIZE * 0.5;
 pub const NEXT_HOLD_BG_WIDTH: f32 = NEXT_HOLD_BLOCK_SIZE * 4.0 + NEXT_HOLD_BG_PADDING * 2.0;
 pub const NEXT_HOLD_BG_HEIGHT: f32 = NEXT_HOLD_BLOCK_SIZE * 2.0 + NEXT_HOLD_BG_PADDING * 2.0;
-pub const NEXT_START_X: f32 =
-    FIELD_PIXEL_WIDTH / 2.0 + NEXT_HOLD_BG_PADDING + NEXT_HOLD_BG_WIDTH / 2.0;
-pub const HOLD_START_X: f32 = -NEXT_START_X;
-pub const NEXT_HOLD_BG_START_Y: f32 = FIELD_PIXEL_HEIGHT / 2.0 - NEXT_HOLD_BG_HEIGHT / 2.0;
 
 #[derive(Debug, Event)]
-pub struct ReceiveGarbageEvent(pub u8);
+pub struct ReceiveGarbageEvent {
+    pub player_id: PlayerId,
+    pub amount: u8,
+}
 
 #[derive(Debug, Event)]
 pub struct HoldEvent;
 
-#[derive(Component, Default)]
+#[derive(Component)]
 pub struct LocalField {
-    pub can_back_to_back: bool,
-    pub combo: u8,
-    pub t_spin: TSpin,
-    pub garbage_amount: u8,
+    pub game: GameState,
     pub target_player_id: Option<PlayerId>,
-    pub next_queue: NextQueue,
-    pub hold: Option<Shape>,
-    pub is_hold_used: bool,
+    pub targeting: TargetingStrategy,
 }
 
-#[derive(Bundle, Default)]
+#[derive(Bundle)]
 pub struct LocalFieldBundle {
     pub local_field: LocalField,
-    pub drop_timer: DropTimer,
-    pub lock_down_timer: LockDownTimer,
     pub target_change_timer: TargetChangeTimer,
 }
 
-#[derive(Component)]
-pub struct GarbageWarningBar;
+// 次の設置でせり上がれる分と，まだ猶予がある分を分けて表示する
+#[derive(Component, Clone, Copy, PartialEq, Eq)]
+pub enum GarbageWarningBar {
+    Ready,
+    Queued,
+}
 
 #[derive(Component)]
 pub struct NextHoldBlock;
 
+impl LocalField {
+    pub fn new(settings: MatchSettings) -> Self {
+        Self {
+            game: GameState::new(settings),
+            target_player_id: None,
+            targeting: TargetingStrategy::default(),
+        }
+    }
+}
+
+impl LocalFieldBundle {
+    pub fn new(settings: MatchSettings) -> Self {
+        Self {
+            local_field: LocalField::new(settings),
+            target_change_timer: TargetChangeTimer::default(),
+        }
+    }
+}
+
 impl GarbageWarningBar {
-    pub fn spawn(parent: &mut ChildBuilder) {
-        parent.spawn((
-            SpriteBundle {
-                transform: Transform::from_translation(Vec3::new(
-                    GARBAGE_WARN_BAR_START_X,
-                    GARBAGE_WARN_BAR_START_Y,
-                    0.0,
-                )),
-                sprite: Sprite {
-                    anchor: Anchor::BottomCenter,
-                    color: GARBAGE_WARN_BAR_COLOR,
+    pub fn spawn(parent: &mut ChildBuilder, size: FieldSize) {
+        for (bar, color) in [
+            (Self::Ready, GARBAGE_WARN_BAR_COLOR),
+            (Self::Queued, GARBAGE_WARN_BAR_QUEUED_COLOR),
+        ] {
+            parent.spawn((
+                SpriteBundle {
+                    transform: Transform::from_translation(Vec3::new(
+                        -size.pixel_width() / 2.0
+                            - GARBAGE_WARN_BAR_WIDTH / 2.0
+                            - GARBAGE_WARN_BAR_INSET,
+                        -size.pixel_height() / 2.0,
+                        0.0,
+                    )),
+                    sprite: Sprite {
+                        anchor: Anchor::BottomCenter,
+                        color,
+                        ..default()
+                    },
                     ..default()
                 },
-                ..default()
-            },
-            GarbageWarningBar,
-        ));
+                bar,
+            ));
+        }
     }
 }
 
-pub fn handle_receive_garbage(
-    mut receive_garbage_events: EventReader<ReceiveGarbageEvent>,
-    mut local_field_query: Query<&mut LocalField>,
+#[allow(clippy::too_many_arguments, clippy::cast_possible_truncation)]
+pub fn local_field_system(
+    time: Res<Time>,
+    mut move_events: EventReader<MoveEvent>,
+    mut hold_events: EventReader<HoldEvent>,
+    mut key_press_events: EventReader<KeyPressEvent>,
+    mut socket: Option<ResMut<Socket>>,
+    players: Option<Res<Players>>,
+    mut field_query: Query<(
+        &mut Field,
+        &mut LocalField,
+        &mut Stats,
+        Option<&mut FinesseChecker>,
+    )>,
+    mut gameover_events: EventWriter<GameOverEvent>,
+    mut clear_events: EventWriter<ClearEvent>,
+    mut finesse_fault_events: EventWriter<FinesseFaultEvent>,
 ) {
-    let Ok(mut local_field) = local_field_query.get_single_mut() else {
+    let Ok((mut field, mut local_field, mut stats, mut finesse_checker)) =
+        field_query.get_single_mut()
+    else {
         return;
     };
-    for ReceiveGarbageEvent(lines) in receive_garbage_events.read() {
-        local_field.garbage_amount += lines;
+
+    for KeyPressEvent(key) in key_press_events.read() {
+        stats.keys += 1;
+        if let Some(finesse_checker) = &mut finesse_checker {
+            if MOVE_KEYS.contains(key) {
+                finesse_checker.add_key();
+            }
+        }
+    }
+
+    let inputs = move_events
+        .read()
+        .map(Input::from)
+        .chain(hold_events.read().map(|_| Input::Hold))
+        .collect::<Vec<_>>();
+    if let Some(finesse_checker) = &mut finesse_checker {
+        if inputs.contains(&Input::Hold) {
+            finesse_checker.hold();
+        }
+    }
+
+    for event in local_field.game.step(&inputs, time.delta()) {
+        match event {
+            GameEvent::MinoPlaced {
+                mino,
+                previous_blocks,
+                clear_lines,
+                clear_type,
+                garbage_lines,
+            } => {
+                stats.add_piece(clear_type, garbage_lines.len());
+                if let Some(is_fault) = finesse_checker
+                    .as_mut()
+                    .and_then(|finesse_checker| finesse_checker.check(&mino, &previous_blocks))
+                {
+                    stats.add_finesse(is_fault);
+                    if is_fault {
+                        finesse_fault_events.send(FinesseFaultEvent);
+                    }
+                }
+                if clear_type.is_notable() {
+                    clear_events.send(ClearEvent {
+                        player_id: field.player.id,
+                        clear_type,
+                    });
+                }
+
+                // フィールドの状態の変更を通知．1人で遊ぶ場合は通信しない
+                if let (Some(socket), Some(players)) = (socket.as_deref_mut(), players.as_deref()) {
+                    sync_local_field_change(
+                        socket,
+                        players,
+                        mino,
+                        clear_lines,
+                        clear_type,
+                        garbage_lines,
+                        stats.keys,
+                    );
+                }
+            }
+            GameEvent::GarbageSent(amount) => {
+                // おじゃま行を送る
+                if let (Some(socket), Some(target_player_id)) =
+                    (socket.as_deref_mut(), local_field.target_player_id)
+                {
+                    send_garbage(socket, target_player_id, amount);
+                }
+            }
+            GameEvent::GameOver(top_out) => {
+                gameover_events.send(GameOverEvent(top_out));
+            }
+        }
     }
+
+    field.blocks = local_field.game.blocks.clone();
 }
 
-pub fn handle_hold(
-    mut commands: Commands,
-    mut events: EventReader<HoldEvent>,
+pub fn handle_receive_garbage(
+    mut receive_garbage_events: EventReader<ReceiveGarbageEvent>,
     mut local_field_query: Query<&mut LocalField>,
-    mut mino_query: Query<(Entity, &Mino)>,
-    mut spawn_mino_events: EventWriter<SpawnMinoEvent>,
 ) {
     let Ok(mut local_field) = local_field_query.get_single_mut() else {
         return;
     };
-    for _ in events.read() {
-        if local_field.is_hold_used {
-            continue;
-        }
-        local_field.is_hold_used = true;
-
-        let Ok((mino_entity, mino)) = mino_query.get_single_mut() else {
-            continue;
-        };
-        commands.entity(mino_entity).despawn_recursive();
-        let next_shape = if let Some(shape) = local_field.hold {
-            shape
-        } else {
-            local_field.next_queue.pop()
-        };
-        local_field.hold = Some(mino.shape);
-
-        spawn_mino_events.send(SpawnMinoEvent(next_shape));
+    for event in receive_garbage_events.read() {
+        local_field
+            .game
+            .receive_garbage(Some(event.player_id), event.amount);
     }
 }
 
+#[allow(clippy::cast_precision_loss)]
 pub fn garbage_warning_bar_system(
-    mut garbage_line_query: Query<(&mut Sprite, &mut Visibility), With<GarbageWarningBar>>,
-    local_field_query: Query<&LocalField>,
+    mut garbage_line_query: Query<(
+        &GarbageWarningBar,
+        &mut Sprite,
+        &mut Transform,
+        &mut Visibility,
+    )>,
+    local_field_query: Query<(&Field, &LocalField)>,
 ) {
-    let Ok((mut sprite, mut visibility)) = garbage_line_query.get_single_mut() else {
-        return;
-    };
-    let Ok(local_field) = local_field_query.get_single() else {
+    let Ok((field, local_field)) = local_field_query.get_single() else {
         return;
     };
+    let bottom_y = -field.size().pixel_height() / 2.0;
+    let ready = local_field.game.garbage_queue.ready();
+    let queued = local_field.game.garbage_queue.total() - ready;
 
-    *visibility = if local_field.garbage_amount == 0 {
-        Visibility::Hidden
-    } else {
-        Visibility::Inherited
-    };
+    for (bar, mut sprite, mut transform, mut visibility) in &mut garbage_line_query {
+        // 猶予がある分は，せり上がれる分の上に積む
+        let (amount, start_y) = match bar {
+            GarbageWarningBar::Ready => (ready, bottom_y),
+            GarbageWarningBar::Queued => (queued, bottom_y + ready as f32 * BLOCK_SIZE),
+        };
 
-    sprite.custom_size = Some(Vec2::new(
-        GARBAGE_WARN_BAR_WIDTH,
-        local_field.garbage_amount as f32 * BLOCK_SIZE - GARBAGE_WARN_BAR_INSET,
-    ));
+        *visibility = if amount == 0 {
+            Visibility::Hidden
+        } else {
+            Visibility::Inherited
+        };
+
+        transform.translation.y = start_y;
+        sprite.custom_size = Some(Vec2::new(
+            GARBAGE_WARN_BAR_WIDTH,
+            amount as f32 * BLOCK_SIZE - GARBAGE_WARN_BAR_INSET,
+        ));
+    }
 }
 
 pub fn next_hold_block_system(
     mut commands: Commands,
     block_query: Query<Entity, With<NextHoldBlock>>,
-    field_query: Query<(Entity, &LocalField), With<LocalField>>,
+    field_query: Query<(Entity, &Field, &LocalField)>,
 ) {
     for entity in block_query.iter() {
         commands.entity(entity).despawn_recursive();
     }
 
-    let Ok((field_entity, field)) = field_query.get_single() else {
+    let Ok((field_entity, field, local_field)) = field_query.get_single() else {
         return;
     };
+    let size = field.size();
+    let rotation_system = local_field.game.rules.rotation_system.get();
     commands.entity(field_entity).with_children(|parent| {
-        for (i, shape) in field.next_queue.queue().iter().enumerate() {
-            let base = next_pos(i);
+        for (i, shape) in local_field.game.next_queue.queue().iter().enumerate() {
+            let base = next_pos(i, size);
+            let blocks = rotation_system.spawn_blocks(*shape);
 
-            for &pos in shape.blocks(Angle::default()) {
-                let translation = base + pos_to_translation(pos, shape.offset_y(), shape.width());
+            for &pos in blocks {
+                let translation = base + pos_to_translation(pos, bottom(blocks), shape.width());
 
                 let bundle = create_next_hold_block_bundle(translation, shape.color());
                 parent.spawn(bundle);
             }
         }
 
-        if let Some(shape) = field.hold {
-            let base = Vec3::new(HOLD_START_X, NEXT_HOLD_BG_START_Y, 0.0);
+        if let Some(shape) = local_field.game.hold {
+            let base = hold_pos(size);
+            let blocks = rotation_system.spawn_blocks(shape);
 
-            for &pos in shape.blocks(Angle::default()) {
-                let translation = base + pos_to_translation(pos, shape.offset_y(), shape.width());
+            for &pos in blocks {
+                let translation = base + pos_to_translation(pos, bottom(blocks), shape.width());
 
                 let bundle = create_next_hold_block_bundle(translation, shape.color());
                 parent.spawn(bundle);
@@ -184,7 +304,7 @@ pub fn next_hold_block_system(
     });
 }
 
-pub fn spawn_next_hold_background(parent: &mut ChildBuilder) {
+pub fn spawn_next_hold_background(parent: &mut ChildBuilder, size: FieldSize) {
     let next_hold_sprite = Sprite {
         color: FIELD_BACKGROUND_COLOR,
         custom_size: Some(Vec2::new(NEXT_HOLD_BG_WIDTH, NEXT_HOLD_BG_HEIGHT)),
@@ -192,7 +312,7 @@ pub fn spawn_next_hold_background(parent: &mut ChildBuilder) {
     };
 
     for i in 0..QUEUE_SIZE {
-        let translation = next_pos(i);
+        let translation = next_pos(i, size);
 
         parent.spawn(SpriteBundle {
             transform: Transform::from_translation(translation),
@@ -202,7 +322,7 @@ pub fn spawn_next_hold_background(parent: &mut ChildBuilder) {
     }
 
     parent.spawn(SpriteBundle {
-        transform: Transform::from_translation(Vec3::new(HOLD_START_X, NEXT_HOLD_BG_START_Y, 0.0)),
+        transform: Transform::from_translation(hold_pos(size)),
         sprite: next_hold_sprite,
         ..default()
     });
@@ -227,15 +347,28 @@ fn create_next_hold_block_bundle(translation: Vec3, color: Color) -> impl Bundle
     )
 }
 
+// ネクストはフィールドの右に，ホールドは左に上端を揃えて並べる
+fn next_start_x(size: FieldSize) -> f32 {
+    size.pixel_width() / 2.0 + NEXT_HOLD_BG_PADDING + NEXT_HOLD_BG_WIDTH / 2.0
+}
+
+fn next_hold_start_y(size: FieldSize) -> f32 {
+    size.pixel_height() / 2.0 - NEXT_HOLD_BG_HEIGHT / 2.0
+}
+
 #[allow(clippy::cast_precision_loss)]
-fn next_pos(i: usize) -> Vec3 {
+pub fn next_pos(i: usize, size: FieldSize) -> Vec3 {
     Vec3::new(
-        NEXT_START_X,
-        NEXT_HOLD_BG_START_Y - (NEXT_HOLD_BG_HEIGHT + NEXT_HOLD_BG_PADDING) * i as f32,
+        next_start_x(size),
+        next_hold_start_y(size) - (NEXT_HOLD_BG_HEIGHT + NEXT_HOLD_BG_PADDING) * i as f32,
         0.0,
     )
 }
 
+pub fn hold_pos(size: FieldSize) -> Vec3 {
+    Vec3::new(-next_start_x(size), next_hold_start_y(size), 0.0)
+}
+
 fn pos_to_translation(pos: Position, offset_y: i8, width: i8) -> Vec3 {
     let x = (pos.x as f32 - width as f32 / 2.0) * NEXT_HOLD_BLOCK_SIZE;
     let y = (pos.y - offset_y - 1) as f32 * NEXT_HOLD_BLOCK_SIZE;
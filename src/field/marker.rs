@@ -0,0 +1,216 @@
+use super::{
+    local::{LocalField, ReceiveGarbageEvent},
+    Field,
+};
+use crate::{
+    game::rules::FieldSize,
+    net::{PlayerState, Players},
+};
+use bevy::{prelude::*, sprite::Anchor};
+use std::time::Duration;
+
+// フィールドの上端からの距離
+static MARKER_OFFSET_Y: f32 = 24.0;
+static TARGET_MARKER_SIZE: f32 = 32.0;
+static TARGET_MARKER_COLOR: Color = Color::rgb(1.0, 0.0, 0.0);
+static ATTACKER_MARKER_SIZE: f32 = 16.0;
+static ATTACKER_MARKER_GAP: f32 = 8.0;
+static ATTACKER_MARKER_COLOR: Color = Color::rgb(1.0, 0.5, 0.0);
+
+static ATTACK_LINE_WIDTH: f32 = 6.0;
+static ATTACK_LINE_COLOR: Color = Color::rgb(1.0, 0.3, 0.0);
+// 線が伸びきるまでの時間と，消えるまでの時間
+const ATTACK_LINE_GROW_DURATION: Duration = Duration::from_millis(250);
+const ATTACK_LINE_FADE_DURATION: Duration = Duration::from_millis(250);
+
+// 自分が狙っている相手のフィールドに表示する
+#[derive(Component)]
+pub struct TargetMarker;
+
+// 自分を狙っている相手の数だけ，自分のフィールドに表示する
+#[derive(Component)]
+pub struct AttackerMarker;
+
+// おじゃま行を送ったプレイヤーから受け取ったプレイヤーへ伸びる線
+#[derive(Component)]
+pub struct AttackLine {
+    from: Vec3,
+    to: Vec3,
+    timer: Timer,
+}
+
+impl TargetMarker {
+    pub fn spawn(parent: &mut ChildBuilder, size: FieldSize) {
+        parent.spawn((
+            TargetMarker,
+            Text2dBundle {
+                transform: Transform::from_translation(Vec3::new(0.0, marker_y(size), 1.0)),
+                text: Text::from_section(
+                    "TARGET",
+                    TextStyle {
+                        font_size: TARGET_MARKER_SIZE,
+                        color: TARGET_MARKER_COLOR,
+                        ..default()
+                    },
+                ),
+                visibility: Visibility::Hidden,
+                ..default()
+            },
+        ));
+    }
+}
+
+impl AttackLine {
+    fn new(from: Vec3, to: Vec3) -> Self {
+        Self {
+            from,
+            to,
+            timer: Timer::new(
+                ATTACK_LINE_GROW_DURATION + ATTACK_LINE_FADE_DURATION,
+                TimerMode::Once,
+            ),
+        }
+    }
+}
+
+pub fn target_marker_system(
+    mut marker_query: Query<(&Parent, &mut Visibility), With<TargetMarker>>,
+    field_query: Query<&Field>,
+    local_field_query: Query<&LocalField>,
+) {
+    let target_player_id = local_field_query
+        .get_single()
+        .ok()
+        .and_then(|local_field| local_field.target_player_id);
+
+    for (parent, mut visibility) in &mut marker_query {
+        let Ok(field) = field_query.get(parent.get()) else {
+            continue;
+        };
+
+        *visibility = if Some(field.player.id) == target_player_id {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+    }
+}
+
+#[allow(clippy::cast_precision_loss)]
+pub fn attacker_marker_system(
+    mut commands: Commands,
+    marker_query: Query<Entity, With<AttackerMarker>>,
+    players: Res<Players>,
+    field_query: Query<(Entity, &Field), With<LocalField>>,
+) {
+    for entity in marker_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+
+    let Ok((field_entity, field)) = field_query.get_single() else {
+        return;
+    };
+    let attacker_count = players
+        .0
+        .iter()
+        .filter(|player| player.state == PlayerState::Playing)
+        .filter(|player| player.target == Some(field.player.id))
+        .count();
+
+    // フィールドの中央に並べる
+    let step = ATTACKER_MARKER_SIZE + ATTACKER_MARKER_GAP;
+    let start_x = -step * (attacker_count as f32 - 1.0) / 2.0;
+    let y = marker_y(field.size());
+    commands.entity(field_entity).with_children(|parent| {
+        for i in 0..attacker_count {
+            parent.spawn((
+                AttackerMarker,
+                SpriteBundle {
+                    transform: Transform::from_translation(Vec3::new(
+                        start_x + step * i as f32,
+                        y,
+                        1.0,
+                    )),
+                    sprite: Sprite {
+                        color: ATTACKER_MARKER_COLOR,
+                        custom_size: Some(Vec2::new(ATTACKER_MARKER_SIZE, ATTACKER_MARKER_SIZE)),
+                        ..default()
+                    },
+                    ..default()
+                },
+            ));
+        }
+    });
+}
+
+pub fn spawn_attack_line_system(
+    mut commands: Commands,
+    mut receive_garbage_events: EventReader<ReceiveGarbageEvent>,
+    field_query: Query<(&Field, &Transform)>,
+    local_field_query: Query<&Transform, With<LocalField>>,
+) {
+    let Ok(my_transform) = local_field_query.get_single() else {
+        return;
+    };
+
+    for event in receive_garbage_events.read() {
+        let Some((_, sender_transform)) = field_query
+            .iter()
+            .find(|(field, _)| field.player.id == event.player_id)
+        else {
+            continue;
+        };
+
+        let from = sender_transform.translation;
+        let to = my_transform.translation;
+        let direction = (to - from).truncate();
+        commands.spawn((
+            AttackLine::new(from, to),
+            SpriteBundle {
+                transform: Transform::from_translation(from.truncate().extend(2.0))
+                    .with_rotation(Quat::from_rotation_z(direction.y.atan2(direction.x))),
+                sprite: Sprite {
+                    color: ATTACK_LINE_COLOR,
+                    anchor: Anchor::CenterLeft,
+                    custom_size: Some(Vec2::new(0.0, ATTACK_LINE_WIDTH)),
+                    ..default()
+                },
+                ..default()
+            },
+        ));
+    }
+}
+
+// 送った側から線が伸び，伸びきった後は送った側から縮みながら消える
+pub fn attack_line_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut line_query: Query<(Entity, &mut AttackLine, &mut Transform, &mut Sprite)>,
+) {
+    for (entity, mut line, mut transform, mut sprite) in &mut line_query {
+        if line.timer.tick(time.delta()).finished() {
+            commands.entity(entity).despawn_recursive();
+            continue;
+        }
+
+        let elapsed = line.timer.elapsed();
+        let length = (line.to - line.from).truncate().length();
+        let (start, end) = if elapsed < ATTACK_LINE_GROW_DURATION {
+            let progress = elapsed.as_secs_f32() / ATTACK_LINE_GROW_DURATION.as_secs_f32();
+            (0.0, progress)
+        } else {
+            let progress = elapsed
+                .saturating_sub(ATTACK_LINE_GROW_DURATION)
+                .as_secs_f32()
+                / ATTACK_LINE_FADE_DURATION.as_secs_f32();
+            (progress, 1.0)
+        };
+
+        transform.translation = line.from.lerp(line.to, start).truncate().extend(2.0);
+        sprite.custom_size = Some(Vec2::new(length * (end - start), ATTACK_LINE_WIDTH));
+    }
+}
+
+fn marker_y(size: FieldSize) -> f32 {
+    size.pixel_height() / 2.0 + MARKER_OFFSET_Y
+}
@@ -1,27 +1,39 @@
+pub mod action;
 pub mod block;
 pub mod blocks;
+pub mod finesse;
+pub mod hud;
 pub mod local;
+pub mod marker;
 pub mod next;
-pub mod timer;
+pub mod stats;
+pub mod target;
 
 use self::{
     block::{BLOCK_INSET, BLOCK_SIZE},
     blocks::Blocks,
-    local::{spawn_next_hold_background, GarbageWarningBar, LocalFieldBundle},
+    hud::Hud,
+    local::{
+        spawn_next_hold_background, GarbageWarningBar, LocalFieldBundle, NEXT_HOLD_BG_PADDING,
+        NEXT_HOLD_BG_WIDTH,
+    },
+    marker::TargetMarker,
+    stats::{Stats, StatsText},
+    target::TargetingText,
 };
 use crate::{
+    game::rules::FieldSize,
     net::{Player, PlayerState},
     pos,
 };
 use bevy::{prelude::*, sprite::Anchor};
 
-pub const FIELD_WIDTH: i8 = 10;
-pub const FIELD_HEIGHT: i8 = 20;
-// この値よりもブロックがせり上がった場合はゲームオーバー
-pub const FIELD_MAX_HEIGHT: i8 = FIELD_HEIGHT + 20;
-
-pub const FIELD_PIXEL_WIDTH: f32 = BLOCK_SIZE * FIELD_WIDTH as f32;
-pub const FIELD_PIXEL_HEIGHT: f32 = BLOCK_SIZE * FIELD_HEIGHT as f32;
+// 自分と相手のフィールドの端の間隔
+pub const FIELD_SPACING: f32 = 400.0;
+// フィールドの上下に空ける余白
+pub const FIELD_MARGIN_Y: f32 = 60.0;
+// 相手が複数の場合に，縮小したフィールドの間に空ける間隔の割合
+pub const REMOTE_FIELD_GAP: f32 = 0.25;
 
 pub const FIELD_BACKGROUND_COLOR: Color = Color::rgb(0.85, 0.85, 0.85);
 
@@ -38,36 +50,104 @@ pub struct Field {
 #[derive(Component)]
 pub struct ResultText;
 
+// 表示に使う大きさ
+impl FieldSize {
+    pub fn pixel_width(self) -> f32 {
+        BLOCK_SIZE * self.width as f32
+    }
+
+    pub fn pixel_height(self) -> f32 {
+        BLOCK_SIZE * self.height as f32
+    }
+
+    // 自分のフィールドの中心のx座標．相手のフィールドは左右反転した位置に置く
+    pub fn local_field_x(self) -> f32 {
+        -(self.pixel_width() + FIELD_SPACING) / 2.0
+    }
+
+    // 相手のフィールドの配置．相手が複数の場合は，1つ分の場所に縮小して格子状に並べる
+    #[allow(clippy::cast_precision_loss)]
+    pub fn remote_field_transform(self, index: usize, count: usize) -> Transform {
+        let columns = (1..count)
+            .find(|columns| columns * columns >= count)
+            .unwrap_or(count.max(1));
+        let rows = count.div_ceil(columns).max(1);
+        let cells = columns.max(rows) as f32;
+        let column = (index % columns) as f32;
+        let row = (index / columns) as f32;
+
+        let step = Vec2::new(self.pixel_width(), self.pixel_height()) / cells;
+        let x = -self.local_field_x() + (column - (columns - 1) as f32 / 2.0) * step.x;
+        let y = ((rows - 1) as f32 / 2.0 - row) * step.y;
+        let scale = if count <= 1 {
+            1.0
+        } else {
+            1.0 / (cells * (1.0 + REMOTE_FIELD_GAP))
+        };
+
+        Transform::from_xyz(x, y, 0.0).with_scale(Vec3::new(scale, scale, 1.0))
+    }
+
+    // 2つのフィールドとネクスト，ホールドを表示するのに必要な大きさ
+    pub fn view_size(self) -> Vec2 {
+        let width = (-self.local_field_x()
+            + self.pixel_width() / 2.0
+            + NEXT_HOLD_BG_PADDING
+            + NEXT_HOLD_BG_WIDTH)
+            * 2.0;
+        let height = self.pixel_height() + FIELD_MARGIN_Y * 2.0;
+
+        Vec2::new(width, height)
+    }
+}
+
 impl Field {
-    pub fn new(player: Player) -> Self {
+    pub fn new(player: Player, size: FieldSize) -> Self {
         Self {
             player,
-            blocks: Blocks::default(),
+            blocks: Blocks::new(size),
         }
     }
 
-    pub fn spawn(self, commands: &mut Commands, is_local_field: bool, translation: Vec3) -> Entity {
+    pub fn size(&self) -> FieldSize {
+        self.blocks.size()
+    }
+
+    pub fn spawn(
+        self,
+        commands: &mut Commands,
+        local_field: Option<LocalFieldBundle>,
+        transform: Transform,
+    ) -> Entity {
+        let size = self.size();
         let mut field_commands = commands.spawn((
-            SpatialBundle::from_transform(Transform::from_translation(translation)),
+            SpatialBundle::from_transform(transform),
             self,
+            Stats::default(),
         ));
 
-        if is_local_field {
+        if let Some(local_field) = local_field {
             field_commands
-                .insert(LocalFieldBundle::default())
+                .insert(local_field)
                 .with_children(|parent| {
-                    spawn_background(parent);
+                    spawn_background(parent, size);
                     spawn_result_text(parent);
+                    StatsText::spawn(parent, size);
 
-                    spawn_next_hold_background(parent);
-                    GarbageWarningBar::spawn(parent);
+                    spawn_next_hold_background(parent, size);
+                    GarbageWarningBar::spawn(parent, size);
+                    TargetingText::spawn(parent, size);
+                    Hud::spawn(parent, size);
                 })
                 .id()
         } else {
             field_commands
                 .with_children(|parent| {
-                    spawn_background(parent);
+                    spawn_background(parent, size);
                     spawn_result_text(parent);
+                    StatsText::spawn(parent, size);
+
+                    TargetMarker::spawn(parent, size);
                 })
                 .id()
         }
@@ -87,7 +167,7 @@ pub fn result_text_system(
             PlayerState::Playing => {
                 text.sections[0].value.replace_range(.., "");
             }
-            PlayerState::GameOver => {
+            PlayerState::GameOver(_) => {
                 text.sections[0].value.replace_range(.., "Lose...");
                 text.sections[0].style.color = RESULT_LOSE_COLOR;
             }
@@ -95,6 +175,10 @@ pub fn result_text_system(
                 text.sections[0].value.replace_range(.., "Win!");
                 text.sections[0].style.color = RESULT_WIN_COLOR;
             }
+            PlayerState::Completed => {
+                text.sections[0].value.replace_range(.., "Complete!");
+                text.sections[0].style.color = RESULT_WIN_COLOR;
+            }
         }
     }
 }
@@ -116,11 +200,11 @@ fn spawn_result_text(parent: &mut ChildBuilder) {
     ));
 }
 
-fn spawn_background(parent: &mut ChildBuilder) {
-    for y in 0..FIELD_HEIGHT {
-        for x in 0..FIELD_WIDTH {
+fn spawn_background(parent: &mut ChildBuilder, size: FieldSize) {
+    for y in 0..size.height {
+        for x in 0..size.width {
             parent.spawn(SpriteBundle {
-                transform: Transform::from_translation(pos!(x, y).translation()),
+                transform: Transform::from_translation(pos!(x, y).translation(size)),
                 sprite: Sprite {
                     anchor: Anchor::BottomLeft,
                     color: FIELD_BACKGROUND_COLOR,
@@ -135,3 +219,50 @@ fn spawn_background(parent: &mut ChildBuilder) {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn single_remote_field_mirrors_local_field() {
+        let size = FieldSize::default();
+        let transform = size.remote_field_transform(0, 1);
+
+        assert_eq!(
+            transform,
+            Transform::from_xyz(-size.local_field_x(), 0.0, 0.0)
+        );
+    }
+
+    #[test]
+    fn remote_fields_fit_in_one_field() {
+        let size = FieldSize::default();
+        let center = Vec2::new(-size.local_field_x(), 0.0);
+        let half = Vec2::new(size.pixel_width(), size.pixel_height()) / 2.0;
+
+        for count in 2..=8 {
+            let rects = (0..count)
+                .map(|i| {
+                    let transform = size.remote_field_transform(i, count);
+                    let half = half * transform.scale.truncate();
+                    let pos = transform.translation.truncate();
+                    (pos - half, pos + half)
+                })
+                .collect::<Vec<_>>();
+
+            for (i, &(min, max)) in rects.iter().enumerate() {
+                assert!(min.cmpge(center - half).all() && max.cmple(center + half).all());
+                // 他のフィールドと重ならない
+                for &(other_min, other_max) in &rects[i + 1..] {
+                    assert!(
+                        max.x <= other_min.x
+                            || other_max.x <= min.x
+                            || max.y <= other_min.y
+                            || other_max.y <= min.y
+                    );
+                }
+            }
+        }
+    }
+}
@@ -1,27 +1,34 @@
-use std::collections::VecDeque;
-
-use crate::mino::shape::Shape;
+use crate::{
+    game::randomizer::{Randomizer, RandomizerKind},
+    mino::shape::Shape,
+};
 use rand::prelude::*;
+use rand_chacha::ChaCha8Rng;
+use std::collections::VecDeque;
 
 pub const QUEUE_SIZE: usize = 6;
 
 pub struct NextQueue {
     queue: VecDeque<Shape>,
-    bag: RandomBag,
+    randomizer: Box<dyn Randomizer>,
+    rng: ChaCha8Rng,
 }
 
-impl Default for NextQueue {
-    fn default() -> Self {
-        let mut bag = RandomBag::new();
-        let queue = (0..QUEUE_SIZE).map(|_| bag.pop()).collect();
-
-        Self { queue, bag }
+impl NextQueue {
+    pub fn new(seed: u64, randomizer: RandomizerKind) -> Self {
+        let mut randomizer = randomizer.create();
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+        let queue = (0..QUEUE_SIZE).map(|_| randomizer.next(&mut rng)).collect();
+
+        Self {
+            queue,
+            randomizer,
+            rng,
+        }
     }
-}
 
-impl NextQueue {
     pub fn pop(&mut self) -> Shape {
-        self.queue.push_back(self.bag.pop());
+        self.queue.push_back(self.randomizer.next(&mut self.rng));
 
         self.queue.pop_front().unwrap()
     }
@@ -30,43 +37,3 @@ impl NextQueue {
         &self.queue
     }
 }
-
-struct RandomBag(Vec<Shape>);
-
-impl RandomBag {
-    pub fn new() -> Self {
-        let mut bag = Self(Vec::with_capacity(Shape::COUNT));
-        bag.fill();
-
-        bag
-    }
-
-    pub fn pop(&mut self) -> Shape {
-        if self.0.is_empty() {
-            self.fill();
-        }
-
-        self.0.pop().unwrap()
-    }
-
-    fn fill(&mut self) {
-        let mut rng = thread_rng();
-
-        self.0 = vec![
-            Shape::I,
-            Shape::J,
-            Shape::L,
-            Shape::O,
-            Shape::S,
-            Shape::T,
-            Shape::Z,
-        ];
-        self.0.shuffle(&mut rng);
-    }
-}
-
-impl Default for RandomBag {
-    fn default() -> Self {
-        Self::new()
-    }
-}
@@ -0,0 +1,228 @@
+use super::{local::LocalField, Field};
+use crate::{
+    game::{rules::FieldSize, ClearType},
+    net::PlayerState,
+};
+use bevy::{prelude::*, sprite::Anchor};
+use std::time::Duration;
+
+static STATS_TEXT_SIZE: f32 = 18.0;
+static STATS_TEXT_COLOR: Color = Color::rgb(0.2, 0.2, 0.2);
+// フィールドの下端からの距離
+static STATS_TEXT_OFFSET_Y: f32 = -STATS_TEXT_SIZE * 0.8;
+static RESULT_STATS_TEXT_SIZE: f32 = 24.0;
+static RESULT_STATS_TEXT_COLOR: Color = Color::rgb(0.0, 0.0, 0.0);
+// 勝敗の下に表示する
+static RESULT_STATS_TEXT_Y: f32 = -50.0;
+
+// フィールドごとの統計
+#[derive(Component, Debug, Clone, Copy, Default)]
+pub struct Stats {
+    pub pieces: u32,
+    pub lines: u32,
+    // 相殺する前の攻撃力の合計
+    pub attack: u32,
+    pub garbage_received: u32,
+    pub garbage_cleared: u32,
+    pub keys: u32,
+    pub elapsed: Duration,
+    // 操作の無駄を調べたミノの数と，無駄があったミノの数
+    pub finesse_checked: u32,
+    pub finesse_faults: u32,
+}
+
+// 試合中はフィールドの下に1行で，試合後は勝敗の下に全ての統計を表示する
+#[derive(Component, Clone, Copy, PartialEq, Eq)]
+pub enum StatsText {
+    Live,
+    Result,
+}
+
+impl Stats {
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn add_piece(&mut self, clear_type: ClearType, garbage_received: usize) {
+        self.pieces += 1;
+        self.lines += clear_type.lines as u32;
+        self.attack += clear_type.attack as u32;
+        self.garbage_cleared += clear_type.garbage_cleared as u32;
+        self.garbage_received += garbage_received as u32;
+    }
+
+    pub fn add_finesse(&mut self, is_fault: bool) {
+        self.finesse_checked += 1;
+        if is_fault {
+            self.finesse_faults += 1;
+        }
+    }
+
+    // 無駄の無かったミノの割合．調べていない場合はNone
+    pub fn finesse(&self) -> Option<f64> {
+        (self.finesse_checked > 0).then(|| {
+            (self.finesse_checked - self.finesse_faults) as f64 / self.finesse_checked as f64
+                * 100.0
+        })
+    }
+
+    // Pieces Per Second
+    pub fn pps(&self) -> f64 {
+        self.per_second(self.pieces)
+    }
+
+    // Attack Per Minute
+    pub fn apm(&self) -> f64 {
+        self.per_second(self.attack) * 60.0
+    }
+
+    // Attack Per Piece
+    pub fn app(&self) -> f64 {
+        self.per_piece(self.attack)
+    }
+
+    // 攻撃と消したおじゃま行の合計を100秒あたりで表す
+    pub fn vs(&self) -> f64 {
+        self.per_second(self.attack + self.garbage_cleared) * 100.0
+    }
+
+    // Keys Per Piece
+    pub fn kpp(&self) -> f64 {
+        self.per_piece(self.keys)
+    }
+
+    fn per_second(&self, value: u32) -> f64 {
+        let seconds = self.elapsed.as_secs_f64();
+        if seconds == 0.0 {
+            0.0
+        } else {
+            value as f64 / seconds
+        }
+    }
+
+    fn per_piece(&self, value: u32) -> f64 {
+        if self.pieces == 0 {
+            0.0
+        } else {
+            value as f64 / self.pieces as f64
+        }
+    }
+}
+
+impl StatsText {
+    pub fn spawn(parent: &mut ChildBuilder, size: FieldSize) {
+        parent.spawn((
+            StatsText::Live,
+            Text2dBundle {
+                transform: Transform::from_translation(Vec3::new(
+                    0.0,
+                    -size.pixel_height() / 2.0 + STATS_TEXT_OFFSET_Y,
+                    1.0,
+                )),
+                text: Text::from_section(
+                    "",
+                    TextStyle {
+                        font_size: STATS_TEXT_SIZE,
+                        color: STATS_TEXT_COLOR,
+                        ..default()
+                    },
+                ),
+                ..default()
+            },
+        ));
+        parent.spawn((
+            StatsText::Result,
+            Text2dBundle {
+                transform: Transform::from_translation(Vec3::new(0.0, RESULT_STATS_TEXT_Y, 1.0)),
+                text: Text::from_section(
+                    "",
+                    TextStyle {
+                        font_size: RESULT_STATS_TEXT_SIZE,
+                        color: RESULT_STATS_TEXT_COLOR,
+                        ..default()
+                    },
+                )
+                .with_justify(JustifyText::Center),
+                text_anchor: Anchor::TopCenter,
+                visibility: Visibility::Hidden,
+                ..default()
+            },
+        ));
+    }
+}
+
+// プレイ中のフィールドのみ時間を進める
+pub fn stats_timer_system(time: Res<Time>, mut field_query: Query<(&Field, &mut Stats)>) {
+    for (field, mut stats) in &mut field_query {
+        if field.player.state == PlayerState::Playing {
+            stats.elapsed += time.delta();
+        }
+    }
+}
+
+pub fn stats_text_system(
+    mut text_query: Query<(&StatsText, &mut Text, &mut Visibility, &Parent)>,
+    field_query: Query<(&Field, &Stats, Option<&LocalField>)>,
+) {
+    for (stats_text, mut text, mut visibility, parent) in &mut text_query {
+        let Ok((field, stats, local_field)) = field_query.get(parent.get()) else {
+            continue;
+        };
+
+        match stats_text {
+            StatsText::Live => {
+                let finesse = stats
+                    .finesse()
+                    .map(|finesse| format!("  FIN {finesse:.1}%"))
+                    .unwrap_or_default();
+                text.sections[0].value = format!(
+                    "PPS {:.2}  APM {:.1}  VS {:.1}{finesse}",
+                    stats.pps(),
+                    stats.apm(),
+                    stats.vs(),
+                );
+            }
+            StatsText::Result => {
+                if field.player.state == PlayerState::Playing {
+                    *visibility = Visibility::Hidden;
+                    continue;
+                }
+
+                *visibility = Visibility::Inherited;
+                // 負けた場合はゲームオーバーの原因を先頭に表示する
+                let top_out = match field.player.state {
+                    PlayerState::GameOver(top_out) => format!("{}\n", top_out.name()),
+                    _ => String::new(),
+                };
+                // 得点とレベルは自分のフィールドのみ分かる．ultraやmarathonはこれで競う
+                let score = local_field
+                    .map(|local_field| {
+                        let game = &local_field.game;
+                        format!(
+                            "Score {}\nLevel {}\n",
+                            game.score.points,
+                            game.gravity.level()
+                        )
+                    })
+                    .unwrap_or_default();
+                let finesse = stats
+                    .finesse()
+                    .map(|finesse| {
+                        format!("\nFinesse {finesse:.1}% ({} faults)", stats.finesse_faults)
+                    })
+                    .unwrap_or_default();
+                text.sections[0].value = format!(
+                    "{top_out}{score}Time {:.2}s\nPieces {}\nLines {}\nAttack {}\nReceived {}\n\
+                     PPS {:.2}\nAPM {:.1}\nAPP {:.2}\nVS {:.1}\nKPP {:.2}{finesse}",
+                    stats.elapsed.as_secs_f64(),
+                    stats.pieces,
+                    stats.lines,
+                    stats.attack,
+                    stats.garbage_received,
+                    stats.pps(),
+                    stats.apm(),
+                    stats.app(),
+                    stats.vs(),
+                    stats.kpp(),
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,256 @@
+use super::{local::LocalField, Field};
+use crate::{
+    game::rules::FieldSize,
+    net::{broadcast_target, Player, PlayerId, PlayerState, Players, Socket},
+};
+use bevy::prelude::*;
+use rand::{seq::SliceRandom, thread_rng};
+use std::time::Duration;
+
+const TARGET_CHANGE_INTERVAL: Duration = Duration::from_secs(1);
+
+static TARGETING_TEXT_SIZE: f32 = 20.0;
+static TARGETING_TEXT_COLOR: Color = Color::rgb(0.2, 0.2, 0.2);
+// 統計の下に表示する
+static TARGETING_TEXT_OFFSET_Y: f32 = -TARGETING_TEXT_SIZE * 1.9;
+
+// 誰におじゃま行を送るかの作戦
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TargetingStrategy {
+    // ランダムな相手を狙う
+    #[default]
+    Random,
+    // 狙われている数が少ない相手を狙う
+    Even,
+    // ゲームオーバーに近い相手を狙う
+    KOs,
+    // 自分を狙っている相手を順番に狙う
+    Attackers,
+    // キー入力で選んだ相手を狙い続ける
+    Manual,
+}
+
+#[derive(Debug, Event)]
+pub enum TargetingEvent {
+    // 作戦を切り替える
+    NextStrategy,
+    // Manualの場合に次の相手を狙う
+    NextTarget,
+}
+
+// 他のプレイヤーが狙う相手を変えた
+#[derive(Debug, Event)]
+pub struct TargetChangeEvent {
+    pub player_id: PlayerId,
+    pub target: Option<PlayerId>,
+}
+
+#[derive(Component)]
+pub struct TargetingText;
+
+#[derive(Component)]
+pub struct TargetChangeTimer(pub Timer);
+
+impl TargetingStrategy {
+    #[must_use]
+    pub fn next(self) -> Self {
+        match self {
+            Self::Random => Self::Even,
+            Self::Even => Self::KOs,
+            Self::KOs => Self::Attackers,
+            Self::Attackers => Self::Manual,
+            Self::Manual => Self::Random,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Random => "Random",
+            Self::Even => "Even",
+            Self::KOs => "KOs",
+            Self::Attackers => "Attackers",
+            Self::Manual => "Manual",
+        }
+    }
+}
+
+impl Default for TargetChangeTimer {
+    fn default() -> Self {
+        Self(Timer::new(TARGET_CHANGE_INTERVAL, TimerMode::Repeating))
+    }
+}
+
+impl TargetingText {
+    pub fn spawn(parent: &mut ChildBuilder, size: FieldSize) {
+        parent.spawn((
+            TargetingText,
+            Text2dBundle {
+                transform: Transform::from_translation(Vec3::new(
+                    0.0,
+                    -size.pixel_height() / 2.0 + TARGETING_TEXT_OFFSET_Y,
+                    1.0,
+                )),
+                text: Text::from_section(
+                    "",
+                    TextStyle {
+                        font_size: TARGETING_TEXT_SIZE,
+                        color: TARGETING_TEXT_COLOR,
+                        ..default()
+                    },
+                ),
+                ..default()
+            },
+        ));
+    }
+}
+
+pub fn targeting_system(
+    time: Res<Time>,
+    mut targeting_events: EventReader<TargetingEvent>,
+    mut socket: ResMut<Socket>,
+    players: Res<Players>,
+    mut local_field_query: Query<(&Field, &mut LocalField, &mut TargetChangeTimer)>,
+    field_query: Query<&Field, Without<LocalField>>,
+) {
+    let Ok((my_field, mut local_field, mut target_change_timer)) =
+        local_field_query.get_single_mut()
+    else {
+        return;
+    };
+
+    let mut is_strategy_changed = false;
+    let mut is_next_target = false;
+    for event in targeting_events.read() {
+        match event {
+            TargetingEvent::NextStrategy => {
+                local_field.targeting = local_field.targeting.next();
+                is_strategy_changed = true;
+            }
+            TargetingEvent::NextTarget => is_next_target = true,
+        }
+    }
+
+    let alive_players = players
+        .0
+        .iter()
+        .filter(|player| player.state == PlayerState::Playing)
+        .collect::<Vec<_>>();
+    let current = local_field.target_player_id;
+    let is_target_alive = current.is_some_and(|id| alive_players.iter().any(|p| p.id == id));
+    let is_timer_finished = target_change_timer.0.tick(time.delta()).just_finished();
+
+    let target = match local_field.targeting {
+        TargetingStrategy::Manual if is_next_target || !is_target_alive => {
+            next_player(&alive_players, current)
+        }
+        TargetingStrategy::Manual => current,
+        strategy if is_strategy_changed || is_timer_finished || !is_target_alive => choose_target(
+            strategy,
+            my_field.player.id,
+            current,
+            &alive_players,
+            &field_query,
+        ),
+        _ => current,
+    };
+
+    if target != current {
+        local_field.target_player_id = target;
+        broadcast_target(&mut socket, &players, target);
+    }
+}
+
+pub fn handle_target_change(
+    mut target_change_events: EventReader<TargetChangeEvent>,
+    mut players: ResMut<Players>,
+) {
+    for event in target_change_events.read() {
+        if let Some(player) = players.0.iter_mut().find(|p| p.id == event.player_id) {
+            player.target = event.target;
+        }
+    }
+}
+
+pub fn targeting_text_system(
+    mut text_query: Query<&mut Text, With<TargetingText>>,
+    local_field_query: Query<&LocalField>,
+) {
+    let Ok(mut text) = text_query.get_single_mut() else {
+        return;
+    };
+    let Ok(local_field) = local_field_query.get_single() else {
+        return;
+    };
+
+    text.sections[0].value = format!("Target: {}", local_field.targeting.name());
+}
+
+fn choose_target(
+    strategy: TargetingStrategy,
+    my_id: PlayerId,
+    current: Option<PlayerId>,
+    alive_players: &[&Player],
+    field_query: &Query<&Field, Without<LocalField>>,
+) -> Option<PlayerId> {
+    match strategy {
+        TargetingStrategy::Random | TargetingStrategy::Manual => {
+            alive_players.choose(&mut thread_rng()).map(|p| p.id)
+        }
+        TargetingStrategy::Even => {
+            // 自分以外から狙われている数
+            let attacked_count = |id: PlayerId| {
+                alive_players
+                    .iter()
+                    .filter(|p| p.target == Some(id))
+                    .count()
+            };
+            let min_count = alive_players.iter().map(|p| attacked_count(p.id)).min();
+            let candidates = alive_players
+                .iter()
+                .filter(|p| Some(attacked_count(p.id)) == min_count)
+                .collect::<Vec<_>>();
+
+            candidates.choose(&mut thread_rng()).map(|p| p.id)
+        }
+        TargetingStrategy::KOs => alive_players
+            .iter()
+            .max_by_key(|p| {
+                field_query
+                    .iter()
+                    .find(|field| field.player.id == p.id)
+                    .map_or(0, |field| field.blocks.height())
+            })
+            .map(|p| p.id),
+        TargetingStrategy::Attackers => {
+            let attackers = alive_players
+                .iter()
+                .filter(|p| p.target == Some(my_id))
+                .copied()
+                .collect::<Vec<_>>();
+
+            if attackers.is_empty() {
+                choose_target(
+                    TargetingStrategy::Random,
+                    my_id,
+                    current,
+                    alive_players,
+                    field_query,
+                )
+            } else {
+                next_player(&attackers, current)
+            }
+        }
+    }
+}
+
+// 候補の中で今狙っている相手の次のプレイヤー
+fn next_player(candidates: &[&Player], current: Option<PlayerId>) -> Option<PlayerId> {
+    let index = current
+        .and_then(|id| candidates.iter().position(|p| p.id == id))
+        .map_or(0, |index| index + 1);
+
+    candidates
+        .get(index)
+        .or_else(|| candidates.first())
+        .map(|p| p.id)
+}
@@ -0,0 +1,132 @@
+use crate::mino::{shape::Shape, spin::Spin};
+use serde::{Deserialize, Serialize};
+use std::fs;
+
+static GUIDELINE: &str = include_str!("../../assets/attack_tables/guideline.ron");
+static TETRIS_99: &str = include_str!("../../assets/attack_tables/tetris99.ron");
+static TETRIO: &str = include_str!("../../assets/attack_tables/tetrio.ron");
+
+// 送るおじゃま行数の表
+// 各表は消したライン数を添字(1ラインが先頭)とし，表にない場合は通常のライン消去の値を使う
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AttackTable {
+    pub lines: Vec<u8>,
+    pub t_spin_mini: Vec<u8>,
+    pub t_spin: Vec<u8>,
+    // Tミノ以外のスピン．指定しない場合は通常のライン消去の値を使う
+    #[serde(default)]
+    pub spin_mini: Vec<u8>,
+    #[serde(default)]
+    pub spin: Vec<u8>,
+    // RENの数を添字とするボーナス．表より長く続いた場合は最後の値を使う
+    pub combo: Vec<u8>,
+    pub back_to_back: u8,
+    // パーフェクトクリアの場合はこの値で固定
+    pub perfect_clear: u8,
+}
+
+impl Default for AttackTable {
+    fn default() -> Self {
+        Self::parse(GUIDELINE).unwrap()
+    }
+}
+
+impl AttackTable {
+    // プリセット名かRONファイルのパスから読み込む
+    pub fn load(name_or_path: &str) -> Result<Self, String> {
+        match name_or_path {
+            "guideline" => Self::parse(GUIDELINE),
+            "tetris99" => Self::parse(TETRIS_99),
+            "tetrio" => Self::parse(TETRIO),
+            path => {
+                let source = fs::read_to_string(path).map_err(|err| format!("{path}: {err}"))?;
+                Self::parse(&source).map_err(|err| format!("{path}: {err}"))
+            }
+        }
+    }
+
+    pub fn parse(source: &str) -> Result<Self, String> {
+        ron::from_str(source).map_err(|err| err.to_string())
+    }
+
+    pub fn attack(
+        &self,
+        lines: usize,
+        spin: Spin,
+        combo: u8,
+        is_back_to_back: bool,
+        is_perfect_clear: bool,
+    ) -> u8 {
+        if lines == 0 {
+            return 0;
+        }
+
+        if is_perfect_clear {
+            return self.perfect_clear;
+        }
+
+        // 基本のおじゃま行数
+        let table = match spin {
+            Spin::None => &self.lines,
+            Spin::Mini(Shape::T) => &self.t_spin_mini,
+            Spin::Full(Shape::T) => &self.t_spin,
+            Spin::Mini(_) => &self.spin_mini,
+            Spin::Full(_) => &self.spin,
+        };
+        let basic = table
+            .get(lines - 1)
+            .or_else(|| self.lines.get(lines - 1))
+            .or_else(|| self.lines.last())
+            .copied()
+            .unwrap_or_default();
+
+        // RENボーナス
+        let combo_bonus = self
+            .combo
+            .get(combo as usize)
+            .or_else(|| self.combo.last())
+            .copied()
+            .unwrap_or_default();
+
+        // Back to Backの場合はボーナス
+        let back_to_back_bonus = if is_back_to_back {
+            self.back_to_back
+        } else {
+            0
+        };
+
+        basic
+            .saturating_add(combo_bonus)
+            .saturating_add(back_to_back_bonus)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn load_presets() {
+        for name in ["guideline", "tetris99", "tetrio"] {
+            let table = AttackTable::load(name);
+            assert!(table.is_ok(), "{name}: {table:?}");
+        }
+    }
+
+    #[test]
+    fn tetris99_combo_differs_from_guideline() {
+        let guideline = AttackTable::load("guideline").unwrap();
+        let tetris99 = AttackTable::load("tetris99").unwrap();
+
+        // 4REN目は，ガイドラインでは2行，Tetris 99では1行
+        assert_eq!(guideline.attack(1, Spin::None, 4, false, false), 2);
+        assert_eq!(tetris99.attack(1, Spin::None, 4, false, false), 1);
+    }
+
+    #[test]
+    fn tetris99_ignores_non_t_spins() {
+        let tetris99 = AttackTable::load("tetris99").unwrap();
+        assert_eq!(tetris99.attack(2, Spin::Full(Shape::S), 0, false, false), 1);
+        assert_eq!(tetris99.attack(2, Spin::Full(Shape::T), 0, false, false), 4);
+    }
+}
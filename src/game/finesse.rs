@@ -0,0 +1,175 @@
+use crate::{
+    field::blocks::Blocks,
+    mino::Mino,
+    movement::{Direction, Rotation},
+    position::Position,
+};
+use std::collections::{HashSet, VecDeque};
+
+// 設置する前のフィールドで，出現位置から設置した位置まで移動するのに必要な最小のキー入力の回数
+// 1マスの移動と回転に加えて，壁まで移動するDAS(長押し)も1回と数える
+// ハードドロップで届かない位置(ソフトドロップしてから動かす位置など)の場合はNone
+pub fn min_key_count(placed: &Mino, blocks: &Blocks) -> Option<u32> {
+    let start = Mino::new(placed.shape, blocks, placed.rotation_system)?;
+    let target = footprint(placed);
+
+    let mut visited = HashSet::from([(start.pos, start.angle)]);
+    let mut queue = VecDeque::from([(start, 0)]);
+    while let Some((mino, count)) = queue.pop_front() {
+        if footprint(&mino.hard_dropped(blocks)) == target {
+            return Some(count);
+        }
+
+        for next in next_minos(&mino, blocks) {
+            if visited.insert((next.pos, next.angle)) {
+                queue.push_back((next, count + 1));
+            }
+        }
+    }
+
+    None
+}
+
+// 1回のキー入力で移動できる位置
+fn next_minos(mino: &Mino, blocks: &Blocks) -> Vec<Mino> {
+    let mut minos = Vec::new();
+
+    for direction in [Direction::Left, Direction::Right] {
+        let delta = direction.move_delta();
+        let tapped = mino.moved(delta);
+        if !blocks.can_place_mino(&tapped) {
+            continue;
+        }
+        minos.push(tapped);
+
+        let mut dashed = tapped;
+        while blocks.can_place_mino(&dashed.moved(delta)) {
+            dashed = dashed.moved(delta);
+        }
+        minos.push(dashed);
+    }
+
+    for rotation in [Rotation::Left, Rotation::Right, Rotation::Half] {
+        if let Some((rotated, _)) = mino.rotated(rotation, blocks) {
+            minos.push(rotated);
+        }
+    }
+
+    minos
+}
+
+// フィールド上でのブロックの配置
+// 回転の状態が違っても同じ位置を埋める場合は同じ位置として扱う
+fn footprint(mino: &Mino) -> Vec<Position> {
+    let mut positions = mino.positions().collect::<Vec<_>>();
+    positions.sort_by_key(|pos| (pos.x, pos.y));
+
+    positions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{field::block::Block, mino::shape::Shape, pos, rotation::RotationSystemKind};
+
+    fn spawn(shape: Shape, blocks: &Blocks) -> Mino {
+        Mino::new(shape, blocks, RotationSystemKind::Srs).unwrap()
+    }
+
+    // 出現位置から指定した入力で動かし，ハードドロップした位置
+    fn placed(
+        mut mino: Mino,
+        inputs: &[Position],
+        rotations: &[Rotation],
+        blocks: &Blocks,
+    ) -> Mino {
+        for &rotation in rotations {
+            mino = mino.rotated(rotation, blocks).unwrap().0;
+        }
+        for &delta in inputs {
+            mino = mino.moved(delta);
+            assert!(blocks.can_place_mino(&mino));
+        }
+
+        mino.hard_dropped(blocks)
+    }
+
+    #[test]
+    fn hard_drop_from_spawn_needs_no_keys() {
+        let blocks = Blocks::default();
+        let o = spawn(Shape::O, &blocks);
+
+        assert_eq!(
+            min_key_count(&placed(o, &[], &[], &blocks), &blocks),
+            Some(0)
+        );
+    }
+
+    #[test]
+    fn das_to_wall_is_one_key() {
+        let blocks = Blocks::default();
+        let o = spawn(Shape::O, &blocks);
+        let to_wall = vec![pos!(-1, 0); o.positions().map(|pos| pos.x).min().unwrap() as usize];
+
+        assert_eq!(
+            min_key_count(&placed(o, &to_wall, &[], &blocks), &blocks),
+            Some(1)
+        );
+    }
+
+    #[test]
+    fn taps_are_counted_one_by_one() {
+        let blocks = Blocks::default();
+        let o = spawn(Shape::O, &blocks);
+
+        assert_eq!(
+            min_key_count(&placed(o, &[pos!(-1, 0)], &[], &blocks), &blocks),
+            Some(1)
+        );
+        assert_eq!(
+            min_key_count(
+                &placed(o, &[pos!(-1, 0), pos!(-1, 0)], &[], &blocks),
+                &blocks
+            ),
+            Some(2)
+        );
+    }
+
+    #[test]
+    fn rotation_and_tap_is_two_keys() {
+        let blocks = Blocks::default();
+        let t = spawn(Shape::T, &blocks);
+
+        assert_eq!(
+            min_key_count(&placed(t, &[], &[Rotation::Right], &blocks), &blocks),
+            Some(1)
+        );
+        assert_eq!(
+            min_key_count(&placed(t, &[], &[Rotation::Half], &blocks), &blocks),
+            Some(1)
+        );
+        assert_eq!(
+            min_key_count(
+                &placed(t, &[pos!(1, 0)], &[Rotation::Right], &blocks),
+                &blocks
+            ),
+            Some(2)
+        );
+    }
+
+    #[test]
+    fn unreachable_by_hard_drop_is_none() {
+        let mut blocks = Blocks::default();
+        for x in 0..6 {
+            *blocks.get_mut(pos!(x, 2)).unwrap() = Block::Garbage;
+        }
+        // 張り出しの下に入れたOミノ
+        let mut o = spawn(Shape::O, &blocks);
+        let min_y = o.positions().map(|pos| pos.y).min().unwrap();
+        let min_x = o.positions().map(|pos| pos.x).min().unwrap();
+        o.pos += pos!(-min_x, -min_y);
+        assert!(blocks.can_place_mino(&o));
+
+        assert_eq!(min_key_count(&o, &blocks), None);
+    }
+}
@@ -0,0 +1,265 @@
+use crate::net::PlayerId;
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use std::{collections::VecDeque, time::Duration};
+
+// 1回の設置でせり上がるおじゃま行の上限
+pub const DEFAULT_GARBAGE_CAP: u8 = 8;
+// 攻撃を受けてからせり上がれるようになるまでの時間
+pub const DEFAULT_GARBAGE_DELAY: Duration = Duration::from_millis(500);
+
+pub const DEFAULT_GARBAGE_MESSINESS: GarbageMessiness = GarbageMessiness {
+    attack: 1.0,
+    row: 0.3,
+};
+
+// おじゃま行の穴の開け方
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize, ValueEnum)]
+pub enum GarbageStyle {
+    // 穴の列が変わる確率を指定する
+    #[default]
+    Messy,
+    // 1回の攻撃では同じ列に穴が開く
+    Clean,
+    // 1行ごとにランダムな列に穴が開く
+    Cheese,
+    // 穴が無く，消せない行がせり上がる
+    Solid,
+}
+
+// 穴の列が前の行から変わる確率
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct GarbageMessiness {
+    // 攻撃の最初の行
+    pub attack: f64,
+    // 攻撃の2行目以降
+    pub row: f64,
+}
+
+// 受け取った1回分の攻撃
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IncomingGarbage {
+    pub sender: Option<PlayerId>,
+    pub amount: u8,
+    // せり上がれるようになるまでの残り時間
+    pub delay: Duration,
+}
+
+// 受け取った攻撃を届いた順に保持する
+#[derive(Debug, Clone)]
+pub struct GarbageQueue {
+    entries: VecDeque<IncomingGarbage>,
+    delay: Duration,
+    cap: u8,
+}
+
+impl GarbageStyle {
+    // 穴が無い場合はNone
+    pub fn messiness(self, messy: GarbageMessiness) -> Option<GarbageMessiness> {
+        match self {
+            Self::Messy => Some(messy),
+            Self::Clean => Some(GarbageMessiness {
+                attack: 1.0,
+                row: 0.0,
+            }),
+            Self::Cheese => Some(GarbageMessiness {
+                attack: 1.0,
+                row: 1.0,
+            }),
+            Self::Solid => None,
+        }
+    }
+}
+
+impl IncomingGarbage {
+    pub fn is_ready(&self) -> bool {
+        self.delay.is_zero()
+    }
+}
+
+impl GarbageQueue {
+    pub fn new(delay: Duration, cap: u8) -> Self {
+        Self {
+            entries: VecDeque::new(),
+            delay,
+            cap,
+        }
+    }
+
+    pub fn push(&mut self, sender: Option<PlayerId>, amount: u8) {
+        if amount == 0 {
+            return;
+        }
+
+        self.entries.push_back(IncomingGarbage {
+            sender,
+            amount,
+            delay: self.delay,
+        });
+    }
+
+    pub fn tick(&mut self, dt: Duration) {
+        for entry in &mut self.entries {
+            entry.delay = entry.delay.saturating_sub(dt);
+        }
+    }
+
+    pub fn entries(&self) -> impl Iterator<Item = &IncomingGarbage> {
+        self.entries.iter()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    // 受け取る予定のおじゃま行の合計
+    pub fn total(&self) -> u32 {
+        self.entries.iter().map(|entry| entry.amount as u32).sum()
+    }
+
+    // 次の設置でせり上がれるおじゃま行の合計
+    pub fn ready(&self) -> u32 {
+        self.entries
+            .iter()
+            .filter(|entry| entry.is_ready())
+            .map(|entry| entry.amount as u32)
+            .sum()
+    }
+
+    // 古い攻撃から相殺し，相殺しきれずに残った分を返す
+    pub fn cancel(&mut self, mut amount: u8) -> u8 {
+        while let Some(entry) = self.entries.front_mut() {
+            if amount == 0 {
+                break;
+            }
+
+            let cancelled = amount.min(entry.amount);
+            amount -= cancelled;
+            entry.amount -= cancelled;
+            if entry.amount == 0 {
+                self.entries.pop_front();
+            }
+        }
+
+        amount
+    }
+
+    // せり上がれる攻撃を古い順に上限まで取り出す
+    // 上限を超えた分は次の設置に持ち越す
+    pub fn take_ready(&mut self) -> Vec<IncomingGarbage> {
+        let mut taken = Vec::new();
+        let mut rest = self.cap;
+
+        while let Some(entry) = self.entries.front_mut() {
+            if rest == 0 || !entry.is_ready() {
+                break;
+            }
+
+            let amount = rest.min(entry.amount);
+            rest -= amount;
+            entry.amount -= amount;
+            taken.push(IncomingGarbage { amount, ..*entry });
+            if entry.amount == 0 {
+                self.entries.pop_front();
+            }
+        }
+
+        taken
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DELAY: Duration = Duration::from_millis(500);
+
+    fn amounts(garbages: &[IncomingGarbage]) -> Vec<u8> {
+        garbages.iter().map(|garbage| garbage.amount).collect()
+    }
+
+    #[test]
+    fn cancel_spans_queued_entries() {
+        let mut queue = GarbageQueue::new(DELAY, DEFAULT_GARBAGE_CAP);
+        queue.push(None, 2);
+        queue.push(None, 3);
+        queue.push(None, 4);
+
+        // 古い攻撃から順に相殺する
+        assert_eq!(queue.cancel(4), 0);
+        assert_eq!(
+            queue
+                .entries()
+                .map(|entry| entry.amount)
+                .collect::<Vec<_>>(),
+            [1, 4]
+        );
+        assert_eq!(queue.total(), 5);
+    }
+
+    #[test]
+    fn cancel_larger_than_queue_returns_rest() {
+        let mut queue = GarbageQueue::new(DELAY, DEFAULT_GARBAGE_CAP);
+        queue.push(None, 2);
+        queue.push(None, 3);
+
+        assert_eq!(queue.cancel(8), 3);
+        assert!(queue.is_empty());
+        assert_eq!(queue.cancel(4), 4);
+    }
+
+    #[test]
+    fn partial_delay_is_not_ready() {
+        let mut queue = GarbageQueue::new(DELAY, DEFAULT_GARBAGE_CAP);
+        queue.push(None, 3);
+
+        queue.tick(DELAY / 2);
+        assert_eq!(queue.ready(), 0);
+        assert!(queue.take_ready().is_empty());
+        assert_eq!(queue.total(), 3);
+
+        queue.tick(DELAY / 2);
+        assert_eq!(queue.ready(), 3);
+        assert_eq!(amounts(&queue.take_ready()), [3]);
+        assert!(queue.is_empty());
+    }
+
+    #[test]
+    fn only_expired_entries_are_taken() {
+        let mut queue = GarbageQueue::new(DELAY, DEFAULT_GARBAGE_CAP);
+        queue.push(None, 2);
+        queue.tick(DELAY / 2);
+        queue.push(None, 4);
+
+        queue.tick(DELAY / 2);
+        assert_eq!(amounts(&queue.take_ready()), [2]);
+        assert_eq!(queue.total(), 4);
+    }
+
+    #[test]
+    fn entries_ready_on_same_tick_are_taken_in_order() {
+        let mut queue = GarbageQueue::new(DELAY, DEFAULT_GARBAGE_CAP);
+        queue.push(None, 1);
+        queue.tick(DELAY / 4);
+        queue.push(None, 2);
+        queue.push(None, 3);
+
+        // 1回の更新で全ての猶予が切れる
+        queue.tick(DELAY * 2);
+        assert_eq!(queue.ready(), 6);
+        assert_eq!(amounts(&queue.take_ready()), [1, 2, 3]);
+        assert!(queue.is_empty());
+    }
+
+    #[test]
+    fn cap_splits_entries_ready_on_same_tick() {
+        let mut queue = GarbageQueue::new(DELAY, 4);
+        queue.push(None, 3);
+        queue.push(None, 3);
+        queue.tick(DELAY);
+
+        assert_eq!(amounts(&queue.take_ready()), [3, 1]);
+        assert_eq!(amounts(&queue.take_ready()), [2]);
+        assert!(queue.is_empty());
+    }
+}
@@ -0,0 +1,187 @@
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+pub const LINES_PER_LEVEL: u32 = 10;
+// ソフトドロップ中に落ちる間隔の上限
+pub const SOFT_DROP_INTERVAL: Duration = Duration::from_millis(50);
+// rampで1レベル上がるまでの時間
+pub const GRAVITY_RAMP_INTERVAL: Duration = Duration::from_secs(30);
+// 1フレーム(1/60秒)に20段落ちる速度を20Gとし，これ以上は即座に接地させる
+pub const TWENTY_G_INTERVAL: Duration = Duration::from_nanos(1_000_000_000 / 60 / 20);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize, ValueEnum)]
+pub enum GravityKind {
+    // 開始時のレベルのまま変わらない
+    #[default]
+    Fixed,
+    // 消したライン数に応じてレベルが上がる
+    Level,
+    // 経過時間に応じてレベルが上がる
+    Ramp,
+}
+
+pub struct Gravity {
+    kind: GravityKind,
+    start_level: u32,
+    lines: u32,
+    elapsed: Duration,
+    // 次に落ちるまでの段数の端数
+    rows: f64,
+    is_soft_drop: bool,
+}
+
+impl Gravity {
+    pub fn new(kind: GravityKind, start_level: u32) -> Self {
+        Self {
+            kind,
+            start_level: start_level.max(1),
+            lines: 0,
+            elapsed: Duration::ZERO,
+            rows: 0.0,
+            is_soft_drop: false,
+        }
+    }
+
+    pub fn level(&self) -> u32 {
+        match self.kind {
+            GravityKind::Fixed => self.start_level,
+            GravityKind::Level => self.start_level + self.lines / LINES_PER_LEVEL,
+            GravityKind::Ramp => {
+                self.start_level
+                    + (self.elapsed.as_secs_f64() / GRAVITY_RAMP_INTERVAL.as_secs_f64()) as u32
+            }
+        }
+    }
+
+    // ガイドラインの落下速度: (0.8 - (レベル - 1) * 0.007)^(レベル - 1) 秒で1段
+    pub fn interval(&self) -> Duration {
+        let level = self.level() as f64 - 1.0;
+        let seconds = (0.8 - level * 0.007).max(0.0).powf(level);
+        let interval = Duration::from_secs_f64(seconds);
+
+        if self.is_soft_drop {
+            interval.min(SOFT_DROP_INTERVAL)
+        } else {
+            interval
+        }
+    }
+
+    pub fn is_soft_drop(&self) -> bool {
+        self.is_soft_drop
+    }
+
+    pub fn set_soft_drop(&mut self, is_soft_drop: bool) {
+        self.is_soft_drop = is_soft_drop;
+    }
+
+    pub fn add_lines(&mut self, lines: u32) {
+        self.lines += lines;
+    }
+
+    pub fn reset(&mut self) {
+        self.rows = 0.0;
+    }
+
+    // 落下させる段数を返す
+    pub fn tick(&mut self, dt: Duration) -> u32 {
+        self.elapsed += dt;
+
+        let interval = self.interval();
+        // 接地するまで落とす
+        if interval <= TWENTY_G_INTERVAL {
+            return u32::MAX;
+        }
+
+        self.rows += dt.as_secs_f64() / interval.as_secs_f64();
+        let rows = self.rows.floor();
+        self.rows -= rows;
+
+        rows as u32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FRAME: Duration = Duration::from_nanos(1_000_000_000 / 60);
+
+    // 1秒分のフレームで落ちる段数
+    fn rows_per_second(gravity: &mut Gravity) -> u32 {
+        (0..60).map(|_| gravity.tick(FRAME)).sum()
+    }
+
+    #[test]
+    fn level_1_falls_one_row_per_second() {
+        let mut gravity = Gravity::new(GravityKind::Fixed, 1);
+        assert_eq!(gravity.interval(), Duration::from_secs(1));
+        assert_eq!(gravity.tick(FRAME), 0);
+        assert_eq!(gravity.tick(Duration::from_secs(1) - FRAME), 1);
+    }
+
+    #[test]
+    fn level_15_falls_about_2_rows_per_frame() {
+        let mut gravity = Gravity::new(GravityKind::Fixed, 15);
+        let rows = rows_per_second(&mut gravity);
+        // ガイドラインでは約2.36G
+        assert!((140..=142).contains(&rows), "{rows} rows");
+    }
+
+    #[test]
+    fn level_20_and_above_is_20g() {
+        for level in [20, 25, 100, 200] {
+            let mut gravity = Gravity::new(GravityKind::Fixed, level);
+            assert_eq!(gravity.tick(FRAME), u32::MAX, "level {level}");
+        }
+    }
+
+    #[test]
+    fn soft_drop_is_capped_by_gravity() {
+        let mut gravity = Gravity::new(GravityKind::Fixed, 1);
+        gravity.set_soft_drop(true);
+        assert_eq!(gravity.interval(), SOFT_DROP_INTERVAL);
+
+        let mut gravity = Gravity::new(GravityKind::Fixed, 20);
+        gravity.set_soft_drop(true);
+        assert!(gravity.interval() < SOFT_DROP_INTERVAL);
+    }
+
+    #[test]
+    fn fixed_ignores_lines() {
+        let mut gravity = Gravity::new(GravityKind::Fixed, 3);
+        gravity.add_lines(LINES_PER_LEVEL * 5);
+        assert_eq!(gravity.level(), 3);
+    }
+
+    #[test]
+    fn level_rises_every_ten_lines() {
+        let mut gravity = Gravity::new(GravityKind::Level, 1);
+        gravity.add_lines(LINES_PER_LEVEL - 1);
+        assert_eq!(gravity.level(), 1);
+        gravity.add_lines(1);
+        assert_eq!(gravity.level(), 2);
+        gravity.add_lines(LINES_PER_LEVEL * 13);
+        assert_eq!(gravity.level(), 15);
+    }
+
+    #[test]
+    fn ramp_only_gets_faster() {
+        let mut gravity = Gravity::new(GravityKind::Ramp, 1);
+        let mut prev_level = gravity.level();
+        let mut prev_interval = gravity.interval();
+
+        // 30分間，1秒ずつ進める
+        for _ in 0..30 * 60 {
+            gravity.tick(Duration::from_secs(1));
+            assert!(gravity.level() >= prev_level);
+            assert!(gravity.interval() <= prev_interval);
+            prev_level = gravity.level();
+            prev_interval = gravity.interval();
+        }
+        assert_eq!(
+            gravity.level(),
+            1 + 30 * 60 / GRAVITY_RAMP_INTERVAL.as_secs() as u32
+        );
+    }
+}
@@ -0,0 +1,212 @@
+use crate::mino::Mino;
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+pub const DEFAULT_LOCK_DOWN_MOVE_LIMIT: u8 = 15;
+// 接地してから固定されるまでの猶予
+pub const LOCK_DOWN_INTERVAL: Duration = Duration::from_millis(500);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize, ValueEnum)]
+pub enum LockDownKind {
+    // 移動や回転で猶予がリセットされるが，回数に上限がある
+    #[default]
+    Extended,
+    // 移動や回転で何度でも猶予がリセットされる
+    Infinite,
+    // より下の段に落ちた場合のみ猶予がリセットされる
+    Step,
+}
+
+pub struct LockDown {
+    kind: LockDownKind,
+    move_limit: u8,
+    // 接地してから経過した時間
+    elapsed: Duration,
+    move_count: u8,
+    lowest_y: i8,
+    // 最も低い段に到達してから接地したか．接地する前の移動や回転は数えない
+    has_landed: bool,
+}
+
+impl LockDown {
+    pub fn new(kind: LockDownKind, move_limit: u8) -> Self {
+        Self {
+            kind,
+            move_limit,
+            elapsed: Duration::ZERO,
+            move_count: 0,
+            lowest_y: i8::MAX,
+            has_landed: false,
+        }
+    }
+
+    pub fn reset(&mut self, mino: &Mino) {
+        self.elapsed = Duration::ZERO;
+        self.move_count = 0;
+        self.lowest_y = lowest_y(mino);
+        self.has_landed = false;
+    }
+
+    // ミノの移動や回転に成功したときに呼ぶ
+    pub fn on_move(&mut self, mino: &Mino) {
+        // これまでより下の段に到達した場合は，回数も含めてリセット
+        if lowest_y(mino) < self.lowest_y {
+            self.reset(mino);
+            return;
+        }
+        if !self.has_landed {
+            return;
+        }
+
+        match self.kind {
+            LockDownKind::Extended => {
+                if self.move_count < self.move_limit {
+                    self.move_count += 1;
+                    self.elapsed = Duration::ZERO;
+                }
+            }
+            LockDownKind::Infinite => self.elapsed = Duration::ZERO,
+            LockDownKind::Step => {}
+        }
+    }
+
+    // ミノを固定すべきときにtrueを返す
+    pub fn tick(&mut self, dt: Duration, is_landed: bool) -> bool {
+        if !is_landed {
+            // 段ごとのリセットでは，浮いている間は猶予が進まないだけでリセットはしない
+            if self.kind != LockDownKind::Step {
+                self.elapsed = Duration::ZERO;
+            }
+            return false;
+        }
+
+        self.has_landed = true;
+
+        // 上限まで移動や回転をした後に着地した場合はすぐに固定する
+        if self.kind == LockDownKind::Extended && self.move_count >= self.move_limit {
+            return true;
+        }
+
+        self.elapsed += dt;
+        if self.elapsed < LOCK_DOWN_INTERVAL {
+            return false;
+        }
+
+        self.elapsed = Duration::ZERO;
+        true
+    }
+}
+
+fn lowest_y(mino: &Mino) -> i8 {
+    mino.positions().map(|pos| pos.y).min().unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        mino::{shape::Shape, Angle},
+        pos,
+        rotation::RotationSystemKind,
+    };
+
+    const MOVE_LIMIT: u8 = 3;
+    // 1回では固定されないが，2回続けると固定される時間
+    const SHORT_WAIT: Duration = LOCK_DOWN_INTERVAL.saturating_sub(Duration::from_millis(100));
+
+    fn mino_at(y: i8) -> Mino {
+        Mino {
+            pos: pos!(3, y),
+            angle: Angle::Deg0,
+            shape: Shape::O,
+            rotation_system: RotationSystemKind::Srs,
+        }
+    }
+
+    fn new_lock_down(kind: LockDownKind) -> LockDown {
+        let mut lock_down = LockDown::new(kind, MOVE_LIMIT);
+        lock_down.reset(&mino_at(10));
+        lock_down
+    }
+
+    #[test]
+    fn extended_ignores_moves_before_landing() {
+        let mut lock_down = new_lock_down(LockDownKind::Extended);
+        for _ in 0..MOVE_LIMIT * 2 {
+            lock_down.on_move(&mino_at(10));
+        }
+
+        assert!(!lock_down.tick(Duration::ZERO, true));
+    }
+
+    #[test]
+    fn extended_locks_immediately_after_move_limit() {
+        let mut lock_down = new_lock_down(LockDownKind::Extended);
+        assert!(!lock_down.tick(Duration::ZERO, true));
+
+        for _ in 0..MOVE_LIMIT {
+            assert!(!lock_down.tick(SHORT_WAIT, true));
+            lock_down.on_move(&mino_at(10));
+        }
+        assert!(lock_down.tick(Duration::ZERO, true));
+    }
+
+    #[test]
+    fn extended_counts_moves_lifted_off_after_landing() {
+        let mut lock_down = new_lock_down(LockDownKind::Extended);
+        assert!(!lock_down.tick(Duration::ZERO, true));
+
+        // 接地した後は，段差から浮いていても数える
+        for _ in 0..MOVE_LIMIT {
+            lock_down.on_move(&mino_at(10));
+            assert!(!lock_down.tick(SHORT_WAIT, false));
+        }
+        assert!(lock_down.tick(Duration::ZERO, true));
+    }
+
+    #[test]
+    fn extended_resets_count_on_lower_row() {
+        let mut lock_down = new_lock_down(LockDownKind::Extended);
+        assert!(!lock_down.tick(Duration::ZERO, true));
+        for _ in 0..MOVE_LIMIT {
+            lock_down.on_move(&mino_at(10));
+        }
+
+        lock_down.on_move(&mino_at(9));
+        assert!(!lock_down.tick(SHORT_WAIT, true));
+    }
+
+    #[test]
+    fn infinite_resets_timer_on_every_move() {
+        let mut lock_down = new_lock_down(LockDownKind::Infinite);
+        for _ in 0..MOVE_LIMIT * 10 {
+            assert!(!lock_down.tick(SHORT_WAIT, true));
+            lock_down.on_move(&mino_at(10));
+        }
+
+        assert!(!lock_down.tick(SHORT_WAIT, true));
+        assert!(lock_down.tick(SHORT_WAIT, true));
+    }
+
+    #[test]
+    fn step_keeps_timer_on_same_row() {
+        let mut lock_down = new_lock_down(LockDownKind::Step);
+        assert!(!lock_down.tick(SHORT_WAIT, true));
+        lock_down.on_move(&mino_at(10));
+        // 浮いている間は進まないが，リセットもしない
+        assert!(!lock_down.tick(SHORT_WAIT, false));
+
+        assert!(lock_down.tick(SHORT_WAIT, true));
+    }
+
+    #[test]
+    fn step_resets_timer_on_lower_row() {
+        let mut lock_down = new_lock_down(LockDownKind::Step);
+        assert!(!lock_down.tick(SHORT_WAIT, true));
+        lock_down.on_move(&mino_at(9));
+
+        assert!(!lock_down.tick(SHORT_WAIT, true));
+        assert!(lock_down.tick(SHORT_WAIT, true));
+    }
+}
@@ -0,0 +1,607 @@
+pub mod attack;
+pub mod finesse;
+pub mod garbage;
+pub mod gravity;
+pub mod lock_down;
+pub mod randomizer;
+pub mod rules;
+pub mod score;
+
+use self::{
+    garbage::GarbageQueue,
+    gravity::Gravity,
+    lock_down::LockDown,
+    rules::{MatchSettings, Rules},
+    score::Score,
+};
+use crate::{
+    field::{
+        blocks::{Blocks, Garbages, Lines},
+        next::NextQueue,
+    },
+    mino::{shape::Shape, spin::Spin, Mino},
+    movement::{Direction, Rotation},
+    net::PlayerId,
+    pos,
+};
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+// Bevyに依存しないゲームのルール
+pub struct GameState {
+    pub rules: Rules,
+    pub blocks: Blocks,
+    pub mino: Option<Mino>,
+    pub next_queue: NextQueue,
+    pub hold: Option<Shape>,
+    pub is_hold_used: bool,
+    pub combo: u8,
+    pub can_back_to_back: bool,
+    pub spin: Spin,
+    pub garbage_queue: GarbageQueue,
+    pub top_out: Option<TopOut>,
+    garbage_rng: ChaCha8Rng,
+    pub gravity: Gravity,
+    pub score: Score,
+    lock_down: LockDown,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Input {
+    Move(Direction),
+    Rotate(Rotation),
+    HardDrop,
+    StartSoftDrop,
+    StopSoftDrop,
+    Hold,
+}
+
+// ミノを設置した時のライン消去の種類
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct ClearType {
+    pub lines: u8,
+    pub spin: Spin,
+    // 連続でラインを消した回数．1回目の消去で1
+    pub combo: u8,
+    pub is_back_to_back: bool,
+    pub is_perfect_clear: bool,
+    // 相殺する前の攻撃力
+    pub attack: u8,
+    // 消したおじゃま行の数
+    pub garbage_cleared: u8,
+}
+
+// ゲームオーバーの原因
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TopOut {
+    // 出現位置がブロックで塞がれている
+    BlockOut,
+    // ミノが全て見えている高さより上で設置された
+    LockOut,
+    // おじゃま行でブロックが上限を超えてせり上がった
+    GarbageOut,
+}
+
+#[derive(Debug, Clone)]
+pub enum GameEvent {
+    MinoPlaced {
+        mino: Mino,
+        // 設置する前のフィールド
+        previous_blocks: Blocks,
+        clear_lines: Lines,
+        clear_type: ClearType,
+        garbage_lines: Garbages,
+    },
+    GarbageSent(u8),
+    GameOver(TopOut),
+}
+
+impl ClearType {
+    // ラインを消したかスピンをした場合に表示する
+    pub fn is_notable(&self) -> bool {
+        self.lines > 0 || !self.spin.is_none()
+    }
+}
+
+impl TopOut {
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::BlockOut => "Block Out",
+            Self::LockOut => "Lock Out",
+            Self::GarbageOut => "Garbage Out",
+        }
+    }
+}
+
+impl GameState {
+    // 同じシードからは同じミノの順番とおじゃま行の穴が生成される
+    pub fn new(settings: MatchSettings) -> Self {
+        let mut garbage_rng = ChaCha8Rng::seed_from_u64(settings.seed);
+        garbage_rng.set_stream(1);
+
+        Self {
+            blocks: Blocks::new(settings.rules.field_size),
+            mino: None,
+            next_queue: NextQueue::new(settings.seed, settings.rules.randomizer),
+            hold: None,
+            is_hold_used: false,
+            combo: 0,
+            can_back_to_back: false,
+            spin: Spin::default(),
+            garbage_queue: GarbageQueue::new(
+                settings.rules.garbage_delay,
+                settings.rules.garbage_cap,
+            ),
+            top_out: None,
+            garbage_rng,
+            gravity: Gravity::new(settings.rules.gravity, settings.rules.start_level),
+            score: Score::default(),
+            lock_down: LockDown::new(settings.rules.lock_down, settings.rules.lock_down_limit),
+            rules: settings.rules,
+        }
+    }
+
+    pub fn step(&mut self, inputs: &[Input], dt: Duration) -> Vec<GameEvent> {
+        let mut events = Vec::new();
+        if self.top_out.is_some() {
+            return events;
+        }
+
+        self.garbage_queue.tick(dt);
+
+        if self.mino.is_none() {
+            let shape = self.next_queue.pop();
+            self.spawn_mino(shape, &mut events);
+        }
+
+        for &input in inputs {
+            if self.top_out.is_some() {
+                return events;
+            }
+            self.handle_input(input, &mut events);
+        }
+        if self.top_out.is_some() {
+            return events;
+        }
+
+        for _ in 0..self.gravity.tick(dt) {
+            if !self.move_mino(Direction::Down) {
+                break;
+            }
+            if self.gravity.is_soft_drop() {
+                self.score.add_soft_drop(1);
+            }
+        }
+
+        let Some(mino) = self.mino else {
+            return events;
+        };
+        if self.lock_down.tick(dt, mino.is_landed(&self.blocks)) {
+            self.place_mino(&mut events);
+        }
+
+        events
+    }
+
+    // ゴーストとして表示する，ハードドロップした場合のミノ
+    pub fn ghost_mino(&self) -> Option<Mino> {
+        self.mino.map(|mino| mino.hard_dropped(&self.blocks))
+    }
+
+    pub fn receive_garbage(&mut self, sender: Option<PlayerId>, amount: u8) {
+        self.garbage_queue.push(sender, amount);
+    }
+
+    // 1行ずつ別の攻撃として穴の位置を決めたおじゃま行を，相殺や猶予なしにすぐにせり上げる
+    // ゲームオーバーになった場合のイベントは呼び出し側で送る
+    pub fn add_cheese(&mut self, rows: u8) -> Result<(), TopOut> {
+        let garbage_lines = Garbages::generate(
+            &vec![1; rows as usize],
+            self.blocks.size().width,
+            self.rules.garbage_style,
+            self.rules.garbage_messiness,
+            &mut self.garbage_rng,
+        );
+
+        if let Err(top_out) = self.blocks.add_garbages(&garbage_lines) {
+            self.top_out = Some(top_out);
+            return Err(top_out);
+        }
+
+        // 操作中のミノに重なった場合は，重ならない位置まで押し上げる
+        if let Some(mino) = self.mino {
+            let pushed = (0..=rows as i8)
+                .map(|dy| mino.moved(pos!(0, dy)))
+                .find(|mino| self.blocks.can_place_mino(mino));
+            let Some(pushed) = pushed else {
+                self.top_out = Some(TopOut::BlockOut);
+                return Err(TopOut::BlockOut);
+            };
+            self.mino = Some(pushed);
+        }
+
+        Ok(())
+    }
+
+    fn handle_input(&mut self, input: Input, events: &mut Vec<GameEvent>) {
+        match input {
+            Input::Move(direction) => {
+                self.move_mino(direction);
+            }
+            Input::Rotate(rotation) => self.rotate_mino(rotation),
+            Input::HardDrop => self.hard_drop(events),
+            Input::StartSoftDrop => self.gravity.set_soft_drop(true),
+            Input::StopSoftDrop => self.gravity.set_soft_drop(false),
+            Input::Hold => self.hold(events),
+        }
+    }
+
+    fn move_mino(&mut self, direction: Direction) -> bool {
+        let Some(mino) = &mut self.mino else {
+            return false;
+        };
+
+        let moved = mino.moved(direction.move_delta());
+        if !self.blocks.can_place_mino(&moved) {
+            return false;
+        }
+        *mino = moved;
+
+        self.spin = Spin::None;
+        self.lock_down.on_move(mino);
+
+        true
+    }
+
+    fn rotate_mino(&mut self, rotation: Rotation) {
+        let Some(mino) = &mut self.mino else {
+            return;
+        };
+
+        if let Some((rotated, delta)) = mino.rotated(rotation, &self.blocks) {
+            *mino = rotated;
+
+            self.spin
+                .update(mino, &self.blocks, delta, rotation, self.rules.spin_rule);
+            self.lock_down.on_move(mino);
+        }
+    }
+
+    fn hard_drop(&mut self, events: &mut Vec<GameEvent>) {
+        let Some(mino) = &mut self.mino else {
+            return;
+        };
+        let dropped = mino.hard_dropped(&self.blocks);
+        self.score
+            .add_hard_drop((mino.pos.y - dropped.pos.y) as u32);
+        *mino = dropped;
+
+        self.place_mino(events);
+    }
+
+    fn hold(&mut self, events: &mut Vec<GameEvent>) {
+        if self.is_hold_used {
+            return;
+        }
+        let Some(mino) = self.mino.take() else {
+            return;
+        };
+        self.is_hold_used = true;
+
+        let next_shape = if let Some(shape) = self.hold {
+            shape
+        } else {
+            self.next_queue.pop()
+        };
+        self.hold = Some(mino.shape);
+
+        self.spawn_mino(next_shape, events);
+    }
+
+    fn spawn_mino(&mut self, shape: Shape, events: &mut Vec<GameEvent>) {
+        self.mino = Mino::new(shape, &self.blocks, self.rules.rotation_system);
+        self.spin = Spin::None;
+        self.gravity.reset();
+
+        if let Some(mino) = &self.mino {
+            self.lock_down.reset(mino);
+        } else {
+            self.game_over(TopOut::BlockOut, events);
+        }
+    }
+
+    fn place_mino(&mut self, events: &mut Vec<GameEvent>) {
+        let Some(mino) = self.mino.take() else {
+            return;
+        };
+
+        let previous_blocks = self.blocks.clone();
+        self.blocks.place_mino(&mino);
+        let is_lock_out = mino
+            .positions()
+            .all(|pos| pos.y >= self.blocks.size().height);
+
+        let clear_lines = self.blocks.get_filled_lines();
+        let garbage_cleared = self.blocks.count_garbage_lines(&clear_lines);
+        self.blocks.clear_lines(&clear_lines);
+
+        // フィールドの状態を更新
+        // 得点はライン数を加算する前のレベルで計算する
+        let level = self.gravity.level();
+        self.gravity.add_lines(clear_lines.len() as u32);
+        let is_perfect_clear = !clear_lines.is_empty() && self.blocks.is_empty();
+        let is_back_to_back = if clear_lines.is_empty() {
+            self.combo = 0;
+            false
+        } else {
+            let is_difficult_clear = self.is_difficult_clear(&clear_lines);
+            let is_back_to_back = self.can_back_to_back && is_difficult_clear;
+            self.can_back_to_back = is_difficult_clear;
+            self.combo = self.combo.saturating_add(1);
+            is_back_to_back
+        };
+
+        self.score.add_clear(
+            level,
+            clear_lines.len(),
+            self.spin,
+            self.combo,
+            is_back_to_back,
+            is_perfect_clear,
+        );
+
+        // おじゃま行を送る
+        let attack = self.rules.attack_table.attack(
+            clear_lines.len(),
+            self.spin,
+            self.combo,
+            is_back_to_back,
+            is_perfect_clear,
+        );
+        // 受け取る予定のおじゃま行を先に相殺し，残りだけを送る
+        let garbage_amount = if self.rules.garbage_cancel {
+            self.garbage_queue.cancel(attack)
+        } else {
+            attack
+        };
+        if garbage_amount != 0 {
+            events.push(GameEvent::GarbageSent(garbage_amount));
+        }
+
+        // おじゃま行を受け取る
+        // 上限を超えた分は次の設置に持ち越す
+        let garbage_amounts = self
+            .garbage_queue
+            .take_ready()
+            .iter()
+            .map(|garbage| garbage.amount)
+            .collect::<Vec<_>>();
+        let garbage_lines = Garbages::generate(
+            &garbage_amounts,
+            self.blocks.size().width,
+            self.rules.garbage_style,
+            self.rules.garbage_messiness,
+            &mut self.garbage_rng,
+        );
+        self.is_hold_used = false;
+        let garbage_result = self.blocks.add_garbages(&garbage_lines);
+
+        let clear_type = ClearType {
+            lines: clear_lines.len() as u8,
+            spin: self.spin,
+            combo: self.combo,
+            is_back_to_back,
+            is_perfect_clear,
+            attack,
+            garbage_cleared: garbage_cleared as u8,
+        };
+        events.push(GameEvent::MinoPlaced {
+            mino,
+            previous_blocks,
+            clear_lines,
+            clear_type,
+            garbage_lines,
+        });
+
+        if is_lock_out {
+            self.game_over(TopOut::LockOut, events);
+        } else if let Err(top_out) = garbage_result {
+            self.game_over(top_out, events);
+        } else {
+            let shape = self.next_queue.pop();
+            self.spawn_mino(shape, events);
+        }
+    }
+
+    fn game_over(&mut self, top_out: TopOut, events: &mut Vec<GameEvent>) {
+        self.top_out = Some(top_out);
+        events.push(GameEvent::GameOver(top_out));
+    }
+
+    // テトリスやスピンといった難しいライン消去か
+    fn is_difficult_clear(&self, clear_lines: &Lines) -> bool {
+        clear_lines.len() == 4 || !self.spin.is_none()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{field::block::Block, pos};
+    use std::ops::Range;
+
+    fn new_game(rules: Rules) -> GameState {
+        GameState::new(MatchSettings { seed: 0, rules })
+    }
+
+    // 指定した行を，hole_xの列を除いて埋める
+    fn fill_rows(blocks: &mut Blocks, rows: Range<i8>, hole_x: Option<i8>) {
+        for y in rows {
+            for x in (0..blocks.size().width).filter(|&x| Some(x) != hole_x) {
+                *blocks.get_mut(pos!(x, y)).unwrap() = Block::Garbage;
+            }
+        }
+    }
+
+    fn top_out(events: &[GameEvent]) -> Option<TopOut> {
+        events.iter().find_map(|event| match event {
+            GameEvent::GameOver(top_out) => Some(*top_out),
+            _ => None,
+        })
+    }
+
+    fn placed_clear_type(events: &[GameEvent]) -> Option<ClearType> {
+        events.iter().find_map(|event| match event {
+            GameEvent::MinoPlaced { clear_type, .. } => Some(*clear_type),
+            _ => None,
+        })
+    }
+
+    #[test]
+    fn hard_drop_clears_filled_line() {
+        let mut game = new_game(Rules::default());
+        game.step(&[], Duration::ZERO);
+
+        // ハードドロップしたミノで最下段が埋まるようにする
+        let ghost = game.ghost_mino().unwrap();
+        let holes = ghost
+            .positions()
+            .filter(|pos| pos.y == 0)
+            .map(|pos| pos.x)
+            .collect::<Vec<_>>();
+        assert!(!holes.is_empty());
+        for x in (0..game.blocks.size().width).filter(|x| !holes.contains(x)) {
+            *game.blocks.get_mut(pos!(x, 0)).unwrap() = Block::Garbage;
+        }
+
+        let events = game.step(&[Input::HardDrop], Duration::ZERO);
+        let clear_type = placed_clear_type(&events).unwrap();
+        assert_eq!(clear_type.lines, 1);
+        assert_eq!(clear_type.garbage_cleared, 1);
+        assert_eq!(game.score.lines, 1);
+        assert_eq!(game.blocks.count_garbage_rows(), 0);
+        assert!(top_out(&events).is_none());
+    }
+
+    #[test]
+    fn received_garbage_rises_after_delay() {
+        let rules = Rules::default();
+        let delay = rules.garbage_delay;
+        let mut game = new_game(rules);
+
+        game.receive_garbage(None, 2);
+        game.step(&[], delay / 2);
+        assert_eq!(game.garbage_queue.ready(), 0);
+
+        game.step(&[], delay);
+        assert_eq!(game.garbage_queue.ready(), 2);
+
+        let events = game.step(&[Input::HardDrop], Duration::ZERO);
+        let garbage_lines = events
+            .iter()
+            .find_map(|event| match event {
+                GameEvent::MinoPlaced { garbage_lines, .. } => Some(garbage_lines.len()),
+                _ => None,
+            })
+            .unwrap();
+        assert_eq!(garbage_lines, 2);
+        assert_eq!(game.blocks.count_garbage_rows(), 2);
+        assert!(game.garbage_queue.is_empty());
+    }
+
+    #[test]
+    fn garbage_above_cap_waits_for_next_piece() {
+        let rules = Rules {
+            garbage_delay: Duration::ZERO,
+            garbage_cap: 3,
+            ..Rules::default()
+        };
+        let mut game = new_game(rules);
+
+        game.receive_garbage(None, 5);
+        game.step(&[Input::HardDrop], Duration::ZERO);
+        assert_eq!(game.blocks.count_garbage_rows(), 3);
+        assert_eq!(game.garbage_queue.total(), 2);
+
+        game.step(&[Input::HardDrop], Duration::ZERO);
+        assert_eq!(game.blocks.count_garbage_rows(), 5);
+        assert!(game.garbage_queue.is_empty());
+    }
+
+    #[test]
+    fn blocked_spawn_is_block_out() {
+        let mut game = new_game(Rules::default());
+        let height = game.blocks.size().height;
+        fill_rows(&mut game.blocks, (height - 4)..(height + 4), None);
+
+        let events = game.step(&[], Duration::ZERO);
+        assert_eq!(top_out(&events), Some(TopOut::BlockOut));
+        assert_eq!(game.top_out, Some(TopOut::BlockOut));
+    }
+
+    #[test]
+    fn locking_above_visible_rows_is_lock_out() {
+        let mut game = new_game(Rules::default());
+        let height = game.blocks.size().height;
+        // 消えないように穴を空けて，見えている行を全て埋める
+        fill_rows(&mut game.blocks, 0..height, Some(0));
+
+        let events = game.step(&[Input::HardDrop], Duration::ZERO);
+        assert!(placed_clear_type(&events).is_some());
+        assert_eq!(top_out(&events), Some(TopOut::LockOut));
+        assert_eq!(game.top_out, Some(TopOut::LockOut));
+    }
+
+    #[test]
+    fn garbage_pushing_blocks_over_the_top_is_garbage_out() {
+        let rules = Rules {
+            garbage_delay: Duration::ZERO,
+            garbage_cap: 30,
+            ..Rules::default()
+        };
+        let mut game = new_game(rules);
+        fill_rows(&mut game.blocks, 0..16, Some(0));
+
+        game.receive_garbage(None, 30);
+        let events = game.step(&[Input::HardDrop], Duration::ZERO);
+        assert_eq!(top_out(&events), Some(TopOut::GarbageOut));
+        assert_eq!(game.top_out, Some(TopOut::GarbageOut));
+    }
+
+    #[test]
+    fn cheese_rises_without_garbage_queue() {
+        let mut game = new_game(Rules::default());
+        game.step(&[], Duration::ZERO);
+
+        // 猶予や上限が無く，ライン消去で相殺もできない
+        game.add_cheese(2).unwrap();
+        assert_eq!(game.blocks.count_garbage_rows(), 2);
+        assert_eq!(game.garbage_queue.total(), 0);
+    }
+
+    #[test]
+    fn cheese_pushes_up_overlapping_mino() {
+        let mut game = new_game(Rules::default());
+        game.step(&[], Duration::ZERO);
+        let landed = game.ghost_mino().unwrap();
+        game.mino = Some(landed);
+
+        game.add_cheese(1).unwrap();
+        let mino = game.mino.unwrap();
+        assert_eq!(mino.pos.y, landed.pos.y + 1);
+        assert!(game.blocks.can_place_mino(&mino));
+    }
+
+    #[test]
+    fn no_input_after_top_out() {
+        let mut game = new_game(Rules::default());
+        let height = game.blocks.size().height;
+        fill_rows(&mut game.blocks, (height - 4)..(height + 4), None);
+        game.step(&[], Duration::ZERO);
+
+        let events = game.step(&[Input::HardDrop], Duration::ZERO);
+        assert!(events.is_empty());
+    }
+}
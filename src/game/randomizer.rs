@@ -0,0 +1,246 @@
+use crate::mino::shape::Shape;
+use clap::ValueEnum;
+use rand::prelude::*;
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+pub trait Randomizer: Send + Sync {
+    fn next(&mut self, rng: &mut ChaCha8Rng) -> Shape;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize, ValueEnum)]
+pub enum RandomizerKind {
+    #[default]
+    #[value(name = "7-bag")]
+    SevenBag,
+    #[value(name = "14-bag")]
+    FourteenBag,
+    #[value(name = "7+1-bag")]
+    SevenPlusOneBag,
+    Random,
+    Tgm,
+    Nes,
+}
+
+impl RandomizerKind {
+    pub fn create(self) -> Box<dyn Randomizer> {
+        match self {
+            Self::SevenBag => Box::new(Bag::new(1, 0)),
+            Self::FourteenBag => Box::new(Bag::new(2, 0)),
+            Self::SevenPlusOneBag => Box::new(Bag::new(1, 1)),
+            Self::Random => Box::new(PureRandom),
+            Self::Tgm => Box::new(History::default()),
+            Self::Nes => Box::new(Reroll::default()),
+        }
+    }
+}
+
+// 全種類のミノを`copies`個ずつと，ランダムなミノを`extras`個入れた袋から順に取り出す
+struct Bag {
+    copies: usize,
+    extras: usize,
+    shapes: Vec<Shape>,
+}
+
+impl Bag {
+    fn new(copies: usize, extras: usize) -> Self {
+        Self {
+            copies,
+            extras,
+            shapes: Vec::with_capacity(Shape::COUNT * copies + extras),
+        }
+    }
+
+    fn fill(&mut self, rng: &mut ChaCha8Rng) {
+        for _ in 0..self.copies {
+            self.shapes.extend(Shape::ALL);
+        }
+        for _ in 0..self.extras {
+            self.shapes.push(*Shape::ALL.choose(rng).unwrap());
+        }
+        self.shapes.shuffle(rng);
+    }
+}
+
+impl Randomizer for Bag {
+    fn next(&mut self, rng: &mut ChaCha8Rng) -> Shape {
+        if self.shapes.is_empty() {
+            self.fill(rng);
+        }
+
+        self.shapes.pop().unwrap()
+    }
+}
+
+struct PureRandom;
+
+impl Randomizer for PureRandom {
+    fn next(&mut self, rng: &mut ChaCha8Rng) -> Shape {
+        *Shape::ALL.choose(rng).unwrap()
+    }
+}
+
+// TGMの直近4個の履歴を使う方式
+// 履歴にあるミノが出た場合は6回まで引き直す
+struct History {
+    history: VecDeque<Shape>,
+    is_first: bool,
+}
+
+const HISTORY_SIZE: usize = 4;
+const HISTORY_ROLLS: usize = 6;
+
+impl Default for History {
+    fn default() -> Self {
+        Self {
+            history: VecDeque::from([Shape::Z, Shape::S, Shape::Z, Shape::S]),
+            is_first: true,
+        }
+    }
+}
+
+impl Randomizer for History {
+    fn next(&mut self, rng: &mut ChaCha8Rng) -> Shape {
+        let shape = if self.is_first {
+            // 最初のミノはS，Z，Oにならない
+            self.is_first = false;
+            *[Shape::I, Shape::J, Shape::L, Shape::T]
+                .choose(rng)
+                .unwrap()
+        } else {
+            let mut shape = *Shape::ALL.choose(rng).unwrap();
+            for _ in 1..HISTORY_ROLLS {
+                if !self.history.contains(&shape) {
+                    break;
+                }
+                shape = *Shape::ALL.choose(rng).unwrap();
+            }
+            shape
+        };
+
+        self.history.push_back(shape);
+        if self.history.len() > HISTORY_SIZE {
+            self.history.pop_front();
+        }
+
+        shape
+    }
+}
+
+// ファミコン版の方式
+// 8面のサイコロを振り，前回と同じミノか8が出た場合は1度だけ引き直す
+#[derive(Default)]
+struct Reroll {
+    prev: Option<Shape>,
+}
+
+impl Randomizer for Reroll {
+    fn next(&mut self, rng: &mut ChaCha8Rng) -> Shape {
+        let shape = match Shape::ALL.get(rng.gen_range(0..=Shape::COUNT)) {
+            Some(&shape) if Some(shape) != self.prev => shape,
+            _ => *Shape::ALL.choose(rng).unwrap(),
+        };
+        self.prev = Some(shape);
+
+        shape
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn draw(randomizer: &mut dyn Randomizer, rng: &mut ChaCha8Rng, count: usize) -> Vec<Shape> {
+        (0..count).map(|_| randomizer.next(rng)).collect()
+    }
+
+    fn count_shape(shapes: &[Shape], shape: Shape) -> usize {
+        shapes.iter().filter(|&&s| s == shape).count()
+    }
+
+    #[test]
+    fn bags_hold_each_shape() {
+        let cases = [
+            (RandomizerKind::SevenBag, 1, 0),
+            (RandomizerKind::FourteenBag, 2, 0),
+            (RandomizerKind::SevenPlusOneBag, 1, 1),
+        ];
+        for (kind, copies, extras) in cases {
+            let mut rng = ChaCha8Rng::seed_from_u64(0);
+            let mut randomizer = kind.create();
+            let bag_size = Shape::COUNT * copies + extras;
+
+            for _ in 0..100 {
+                let bag = draw(&mut *randomizer, &mut rng, bag_size);
+                for shape in Shape::ALL {
+                    let count = count_shape(&bag, shape);
+                    assert!(
+                        (copies..=copies + extras).contains(&count),
+                        "{kind:?}: {shape:?} appeared {count} times in {bag:?}",
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn tgm_first_piece_is_not_s_z_o() {
+        for seed in 0..100 {
+            let mut rng = ChaCha8Rng::seed_from_u64(seed);
+            let shape = History::default().next(&mut rng);
+            assert!(![Shape::S, Shape::Z, Shape::O].contains(&shape));
+        }
+    }
+
+    #[test]
+    fn tgm_rerolls_recent_pieces_within_budget() {
+        let mut rng = ChaCha8Rng::seed_from_u64(0);
+        let mut randomizer = History::default();
+        randomizer.next(&mut rng);
+
+        for _ in 0..1000 {
+            let history = randomizer.history.clone();
+            // 同じ乱数で引いた場合の候補
+            let mut expected_rng = rng.clone();
+            let rolls = (0..HISTORY_ROLLS)
+                .map(|_| *Shape::ALL.choose(&mut expected_rng).unwrap())
+                .collect::<Vec<_>>();
+            let expected = rolls
+                .iter()
+                .find(|shape| !history.contains(shape))
+                .unwrap_or(rolls.last().unwrap());
+
+            assert_eq!(randomizer.next(&mut rng), *expected);
+        }
+    }
+
+    #[test]
+    fn nes_rerolls_repeat() {
+        let mut rng = ChaCha8Rng::seed_from_u64(0);
+        let mut randomizer = Reroll::default();
+        let shapes = draw(&mut randomizer, &mut rng, 10000);
+
+        // 引き直しが無い場合は1/7の確率で前回と同じになる
+        let repeats = shapes.windows(2).filter(|pair| pair[0] == pair[1]).count();
+        assert!(repeats < shapes.len() / 14, "{repeats} repeats");
+    }
+
+    #[test]
+    fn nes_rerolls_once_on_repeat_or_eight() {
+        let mut rng = ChaCha8Rng::seed_from_u64(0);
+        let mut randomizer = Reroll::default();
+        randomizer.next(&mut rng);
+
+        for _ in 0..1000 {
+            let prev = randomizer.prev;
+            let mut expected_rng = rng.clone();
+            let expected = match Shape::ALL.get(expected_rng.gen_range(0..=Shape::COUNT)) {
+                Some(&shape) if Some(shape) != prev => shape,
+                _ => *Shape::ALL.choose(&mut expected_rng).unwrap(),
+            };
+
+            assert_eq!(randomizer.next(&mut rng), expected);
+        }
+    }
+}
@@ -0,0 +1,92 @@
+use super::{
+    attack::AttackTable,
+    garbage::{
+        GarbageMessiness, GarbageStyle, DEFAULT_GARBAGE_CAP, DEFAULT_GARBAGE_DELAY,
+        DEFAULT_GARBAGE_MESSINESS,
+    },
+    gravity::GravityKind,
+    lock_down::{LockDownKind, DEFAULT_LOCK_DOWN_MOVE_LIMIT},
+    randomizer::RandomizerKind,
+};
+use crate::{mino::spin::SpinRule, rotation::RotationSystemKind};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+pub const DEFAULT_FIELD_WIDTH: i8 = 10;
+pub const DEFAULT_FIELD_HEIGHT: i8 = 20;
+// 見えている高さより上に積める段数．これを超えてせり上がった場合はゲームオーバー
+pub const FIELD_HIDDEN_HEIGHT: i8 = 20;
+
+// 盤面の大きさ．試合のルールで変えられる
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FieldSize {
+    pub width: i8,
+    pub height: i8,
+}
+
+// 試合ごとに選べるルール
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Rules {
+    pub rotation_system: RotationSystemKind,
+    pub randomizer: RandomizerKind,
+    pub lock_down: LockDownKind,
+    pub lock_down_limit: u8,
+    pub gravity: GravityKind,
+    pub start_level: u32,
+    pub attack_table: AttackTable,
+    // 送るおじゃま行で受け取る予定のおじゃま行を相殺するか
+    pub garbage_cancel: bool,
+    // 攻撃を受けてからせり上がれるようになるまでの時間
+    pub garbage_delay: Duration,
+    // 1回の設置でせり上がるおじゃま行の上限
+    pub garbage_cap: u8,
+    pub garbage_style: GarbageStyle,
+    // messyの場合のみ使われる
+    pub garbage_messiness: GarbageMessiness,
+    pub spin_rule: SpinRule,
+    pub field_size: FieldSize,
+}
+
+impl Default for Rules {
+    fn default() -> Self {
+        Self {
+            rotation_system: RotationSystemKind::default(),
+            randomizer: RandomizerKind::default(),
+            lock_down: LockDownKind::default(),
+            lock_down_limit: DEFAULT_LOCK_DOWN_MOVE_LIMIT,
+            gravity: GravityKind::default(),
+            start_level: 1,
+            attack_table: AttackTable::default(),
+            garbage_cancel: true,
+            garbage_delay: DEFAULT_GARBAGE_DELAY,
+            garbage_cap: DEFAULT_GARBAGE_CAP,
+            garbage_style: GarbageStyle::default(),
+            garbage_messiness: DEFAULT_GARBAGE_MESSINESS,
+            spin_rule: SpinRule::default(),
+            field_size: FieldSize::default(),
+        }
+    }
+}
+
+impl Default for FieldSize {
+    fn default() -> Self {
+        Self {
+            width: DEFAULT_FIELD_WIDTH,
+            height: DEFAULT_FIELD_HEIGHT,
+        }
+    }
+}
+
+impl FieldSize {
+    // この高さよりもブロックがせり上がった場合はゲームオーバー
+    pub fn max_height(self) -> i8 {
+        self.height + FIELD_HIDDEN_HEIGHT
+    }
+}
+
+// 試合の開始時にホストが決定し，全員で共有する設定
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MatchSettings {
+    pub seed: u64,
+    pub rules: Rules,
+}
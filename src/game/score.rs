@@ -0,0 +1,147 @@
+use crate::mino::spin::Spin;
+
+// ガイドラインの得点．値はレベル倍される
+static LINE_CLEAR_POINTS: [u64; 5] = [0, 100, 300, 500, 800];
+static SPIN_MINI_POINTS: [u64; 3] = [100, 200, 400];
+static SPIN_POINTS: [u64; 4] = [400, 800, 1200, 1600];
+static PERFECT_CLEAR_POINTS: [u64; 5] = [0, 800, 1200, 1800, 2000];
+static BACK_TO_BACK_PERFECT_CLEAR_POINTS: u64 = 3200;
+static COMBO_POINTS: u64 = 50;
+static SOFT_DROP_POINTS: u64 = 1;
+static HARD_DROP_POINTS: u64 = 2;
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Score {
+    pub points: u64,
+    pub lines: u32,
+}
+
+impl Score {
+    pub fn add_soft_drop(&mut self, cells: u32) {
+        self.points += cells as u64 * SOFT_DROP_POINTS;
+    }
+
+    pub fn add_hard_drop(&mut self, cells: u32) {
+        self.points += cells as u64 * HARD_DROP_POINTS;
+    }
+
+    // ミノを設置した時の得点を加算し，加算した値を返す
+    pub fn add_clear(
+        &mut self,
+        level: u32,
+        lines: usize,
+        spin: Spin,
+        combo: u8,
+        is_back_to_back: bool,
+        is_perfect_clear: bool,
+    ) -> u64 {
+        let table: &[u64] = match spin {
+            Spin::None => &LINE_CLEAR_POINTS,
+            Spin::Mini(_) => &SPIN_MINI_POINTS,
+            Spin::Full(_) => &SPIN_POINTS,
+        };
+        let basic = table
+            .get(lines)
+            .or_else(|| LINE_CLEAR_POINTS.get(lines))
+            .or_else(|| LINE_CLEAR_POINTS.last())
+            .copied()
+            .unwrap_or_default();
+
+        // Back to Backの場合は1.5倍
+        let basic = if is_back_to_back {
+            basic * 3 / 2
+        } else {
+            basic
+        };
+
+        // RENボーナスは2回目の連続消去から
+        let combo_bonus = COMBO_POINTS * combo.saturating_sub(1) as u64;
+
+        let perfect_clear_bonus = match (is_perfect_clear, is_back_to_back, lines) {
+            (false, _, _) => 0,
+            (true, true, 4) => BACK_TO_BACK_PERFECT_CLEAR_POINTS,
+            (true, _, lines) => PERFECT_CLEAR_POINTS
+                .get(lines)
+                .or_else(|| PERFECT_CLEAR_POINTS.last())
+                .copied()
+                .unwrap_or_default(),
+        };
+
+        let points = (basic + combo_bonus + perfect_clear_bonus) * level as u64;
+        self.points += points;
+        self.lines += lines as u32;
+
+        points
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mino::shape::Shape;
+
+    fn add_clear(
+        level: u32,
+        lines: usize,
+        spin: Spin,
+        combo: u8,
+        is_back_to_back: bool,
+        is_perfect_clear: bool,
+    ) -> u64 {
+        Score::default().add_clear(level, lines, spin, combo, is_back_to_back, is_perfect_clear)
+    }
+
+    #[test]
+    fn line_clear_points() {
+        assert_eq!(add_clear(1, 0, Spin::None, 0, false, false), 0);
+        assert_eq!(add_clear(1, 1, Spin::None, 1, false, false), 100);
+        assert_eq!(add_clear(1, 4, Spin::None, 1, false, false), 800);
+        assert_eq!(add_clear(1, 0, Spin::Full(Shape::T), 0, false, false), 400);
+        assert_eq!(add_clear(1, 2, Spin::Full(Shape::T), 1, false, false), 1200);
+        assert_eq!(add_clear(1, 1, Spin::Mini(Shape::T), 1, false, false), 200);
+        // Miniの表にない行数は通常の消去として扱う
+        assert_eq!(add_clear(1, 3, Spin::Mini(Shape::T), 1, false, false), 500);
+    }
+
+    #[test]
+    fn back_to_back_is_one_and_a_half_times() {
+        assert_eq!(add_clear(1, 4, Spin::None, 1, true, false), 1200);
+        assert_eq!(add_clear(1, 3, Spin::Full(Shape::T), 1, true, false), 2400);
+    }
+
+    #[test]
+    fn combo_bonus_starts_from_second_clear() {
+        assert_eq!(add_clear(1, 1, Spin::None, 1, false, false), 100);
+        assert_eq!(add_clear(1, 1, Spin::None, 2, false, false), 150);
+        assert_eq!(add_clear(1, 1, Spin::None, 5, false, false), 300);
+    }
+
+    #[test]
+    fn perfect_clear_bonus() {
+        assert_eq!(add_clear(1, 1, Spin::None, 1, false, true), 100 + 800);
+        assert_eq!(add_clear(1, 4, Spin::None, 1, false, true), 800 + 2000);
+        // Back to BackのTetrisによるパーフェクトクリア
+        assert_eq!(add_clear(1, 4, Spin::None, 1, true, true), 1200 + 3200);
+    }
+
+    #[test]
+    fn points_are_multiplied_by_level() {
+        let base = add_clear(1, 2, Spin::Full(Shape::T), 3, true, false);
+        assert_eq!(
+            add_clear(7, 2, Spin::Full(Shape::T), 3, true, false),
+            base * 7
+        );
+    }
+
+    #[test]
+    fn accumulates_points_and_lines() {
+        let mut score = Score::default();
+        score.add_hard_drop(10);
+        score.add_soft_drop(3);
+        let points = score.add_clear(2, 4, Spin::None, 1, false, false);
+
+        assert_eq!(points, 1600);
+        assert_eq!(score.points, 20 + 3 + 1600);
+        assert_eq!(score.lines, 4);
+    }
+}
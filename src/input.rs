@@ -3,16 +3,32 @@ use std::time::Duration;
 use bevy::prelude::*;
 
 use crate::{
-    field::local::HoldEvent,
-    movement::{Direction, MoveEvent},
+    field::{local::HoldEvent, target::TargetingEvent},
+    movement::{Direction, MoveEvent, Rotation},
 };
 
+// 移動と回転に使うキー．練習では操作の無駄を調べるのに数える
+pub static MOVE_KEYS: [KeyCode; 5] = [
+    KeyCode::ArrowLeft,
+    KeyCode::ArrowRight,
+    KeyCode::KeyZ,
+    KeyCode::KeyX,
+    KeyCode::KeyA,
+];
+// ドロップとホールドに使うキー
+static DROP_AND_HOLD_KEYS: [KeyCode; 3] =
+    [KeyCode::ArrowUp, KeyCode::ArrowDown, KeyCode::ShiftLeft];
+
 const MOVE_REPLEAT_DELAY: Duration = Duration::from_millis(300);
 const MOVE_REPLEAT_INTERVAL: Duration = Duration::from_millis(30);
 
 #[derive(Resource)]
 pub struct KeyboardRepeatTimer(Timer);
 
+// 操作に使うキーが押された
+#[derive(Event)]
+pub struct KeyPressEvent(pub KeyCode);
+
 impl Default for KeyboardRepeatTimer {
     fn default() -> Self {
         Self(Timer::from_seconds(
@@ -28,7 +44,16 @@ pub fn keyboard_input_system(
     mut repeat_timer: ResMut<KeyboardRepeatTimer>,
     mut move_event_writer: EventWriter<MoveEvent>,
     mut hold_event_writer: EventWriter<HoldEvent>,
+    mut targeting_event_writer: EventWriter<TargetingEvent>,
+    mut key_press_event_writer: EventWriter<KeyPressEvent>,
 ) {
+    // 操作に使うキーは全て統計で数える
+    for &key in MOVE_KEYS.iter().chain(&DROP_AND_HOLD_KEYS) {
+        if keyboard_input.just_pressed(key) {
+            key_press_event_writer.send(KeyPressEvent(key));
+        }
+    }
+
     if keyboard_input.just_pressed(KeyCode::ArrowLeft) {
         repeat_timer.0.set_duration(MOVE_REPLEAT_DELAY);
         repeat_timer.0.reset();
@@ -57,9 +82,11 @@ pub fn keyboard_input_system(
     }
 
     if keyboard_input.just_pressed(KeyCode::KeyZ) {
-        move_event_writer.send(MoveEvent::Rotate(Direction::Left));
+        move_event_writer.send(MoveEvent::Rotate(Rotation::Left));
     } else if keyboard_input.just_pressed(KeyCode::KeyX) {
-        move_event_writer.send(MoveEvent::Rotate(Direction::Right));
+        move_event_writer.send(MoveEvent::Rotate(Rotation::Right));
+    } else if keyboard_input.just_pressed(KeyCode::KeyA) {
+        move_event_writer.send(MoveEvent::Rotate(Rotation::Half));
     }
 
     if keyboard_input.just_pressed(KeyCode::ArrowUp) {
@@ -74,4 +101,11 @@ pub fn keyboard_input_system(
     if keyboard_input.just_pressed(KeyCode::ShiftLeft) {
         hold_event_writer.send(HoldEvent);
     }
+
+    if keyboard_input.just_pressed(KeyCode::KeyC) {
+        targeting_event_writer.send(TargetingEvent::NextStrategy);
+    }
+    if keyboard_input.just_pressed(KeyCode::KeyV) {
+        targeting_event_writer.send(TargetingEvent::NextTarget);
+    }
 }
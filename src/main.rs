@@ -10,11 +10,14 @@ pub mod args;
 )]
 pub mod field;
 pub mod fps;
+pub mod game;
 pub mod input;
 pub mod mino;
+pub mod mode;
 pub mod movement;
 pub mod net;
 pub mod position;
+pub mod rotation;
 pub mod state;
 
 use args::Args;
@@ -27,30 +30,57 @@ use bevy::{
 };
 use clap::Parser;
 use field::{
+    action::{action_text_system, spawn_action_text_system, ClearEvent},
     block::field_block_system,
+    finesse::{finesse_warning_system, FinesseFaultEvent},
+    hud::hud_system,
     local::{
-        garbage_warning_bar_system, handle_hold, handle_receive_garbage, next_hold_block_system,
-        HoldEvent, LocalField, ReceiveGarbageEvent,
+        garbage_warning_bar_system, handle_receive_garbage, local_field_system,
+        next_hold_block_system, HoldEvent, ReceiveGarbageEvent,
+    },
+    marker::{
+        attack_line_system, attacker_marker_system, spawn_attack_line_system, target_marker_system,
     },
     result_text_system,
-    timer::{drop_timer_system, lock_down_timer_system, target_change_timer_system},
+    stats::{stats_text_system, stats_timer_system},
+    target::{
+        handle_target_change, targeting_system, targeting_text_system, TargetChangeEvent,
+        TargetingEvent,
+    },
 };
 use fps::{fps_system, setup_fps};
-use input::{keyboard_input_system, KeyboardRepeatTimer};
-use mino::event::{
-    handle_place_mino, handle_spawn_mino, handle_sync_field_change, PlaceMinoEvent, SpawnMinoEvent,
-    SyncFieldChangeEvent,
+use input::{keyboard_input_system, KeyPressEvent, KeyboardRepeatTimer};
+use mino::event::{handle_sync_field_change, SyncFieldChangeEvent};
+use mode::{
+    countdown::{cleanup_countdown, countdown_system, setup_countdown},
+    dig::{dig_hud_system, dig_system, Dig},
+    marathon::{marathon_hud_system, marathon_system, Marathon},
+    setup_mode_system, setup_offline_system,
+    sprint::{sprint_hud_system, sprint_system, Sprint},
+    ultra::{ultra_hud_system, ultra_system, Ultra},
+};
+use movement::MoveEvent;
+use net::{
+    receive_message_system, setup_matchbox_socket, waiting_for_player_system, CurrentMatch, Socket,
 };
-use movement::{handle_move, MoveEvent};
-use net::{receive_message_system, setup_matchbox_socket, waiting_for_player_system};
 use state::{handle_gameover, handle_state_change, AppState, GameOverEvent, StateChangeEvent};
 
 const WINDOW_WIDTH: f32 = 1280.0;
 const WINDOW_HEIGHT: f32 = 720.0;
 const WINDOW_ASPECT: f32 = WINDOW_WIDTH / WINDOW_HEIGHT;
 
+#[derive(Component)]
+struct Background;
+
 fn main() {
     let args = Args::parse();
+    args.validate().unwrap_or_else(|err| err.exit());
+    // 通信しない場合はマッチングを待たずに始める
+    let initial_state = if args.is_offline() {
+        AppState::Countdown
+    } else {
+        AppState::MatchMaking
+    };
 
     App::new()
         .insert_resource(ClearColor(Color::BLACK))
@@ -72,95 +102,184 @@ fn main() {
                 }),
         )
         .add_plugins(FrameTimeDiagnosticsPlugin)
-        .init_state::<AppState>()
-        .add_event::<SpawnMinoEvent>()
-        .add_event::<PlaceMinoEvent>()
+        .insert_state(initial_state)
         .add_event::<MoveEvent>()
         .add_event::<HoldEvent>()
         .add_event::<ReceiveGarbageEvent>()
         .add_event::<SyncFieldChangeEvent>()
         .add_event::<GameOverEvent>()
         .add_event::<StateChangeEvent>()
+        .add_event::<TargetingEvent>()
+        .add_event::<TargetChangeEvent>()
+        .add_event::<ClearEvent>()
+        .add_event::<KeyPressEvent>()
+        .add_event::<FinesseFaultEvent>()
         .insert_resource(KeyboardRepeatTimer::default())
         .add_systems(Startup, (setup, setup_fps))
+        .add_systems(
+            Startup,
+            setup_offline_system.run_if(|args: Res<Args>| args.is_offline()),
+        )
         .add_systems(Update, (camera_system, fps_system))
         .add_systems(OnEnter(AppState::MatchMaking), setup_matchbox_socket)
         .add_systems(
             Update,
             waiting_for_player_system.run_if(in_state(AppState::MatchMaking)),
         )
-        .add_systems(OnEnter(AppState::Playing), setup_game)
+        .add_systems(
+            OnEnter(AppState::Countdown),
+            (setup_mode_system, setup_countdown),
+        )
+        .add_systems(
+            Update,
+            countdown_system.run_if(in_state(AppState::Countdown)),
+        )
+        .add_systems(OnExit(AppState::Countdown), cleanup_countdown)
         .add_systems(PreUpdate, (field_block_system, next_hold_block_system))
         .add_systems(
             Update,
             (
-                receive_message_system,
                 result_text_system,
                 handle_sync_field_change,
                 handle_state_change,
+                target_marker_system,
+                attack_line_system,
+                spawn_action_text_system,
+                action_text_system,
+                stats_text_system,
             )
                 .run_if(in_state(AppState::Playing).or_else(in_state(AppState::Finished))),
         )
         .add_systems(
             Update,
             (
-                drop_timer_system,
-                lock_down_timer_system,
-                target_change_timer_system,
+                hud_system,
+                stats_timer_system,
+                finesse_warning_system,
                 keyboard_input_system,
                 garbage_warning_bar_system,
-                handle_move,
-                handle_place_mino,
-                handle_spawn_mino.after(handle_place_mino),
-                handle_hold,
+                local_field_system.after(keyboard_input_system),
                 handle_receive_garbage,
-                handle_gameover,
+                spawn_attack_line_system,
+                handle_gameover.after(local_field_system),
             )
                 .run_if(in_state(AppState::Playing)),
         )
+        // 対戦の場合のみ
+        .add_systems(
+            Update,
+            (
+                receive_message_system,
+                handle_target_change,
+                attacker_marker_system,
+            )
+                .run_if(resource_exists::<Socket>)
+                .run_if(in_state(AppState::Playing).or_else(in_state(AppState::Finished))),
+        )
+        .add_systems(
+            Update,
+            (
+                targeting_system.after(keyboard_input_system),
+                targeting_text_system,
+            )
+                .run_if(resource_exists::<Socket>)
+                .run_if(in_state(AppState::Playing)),
+        )
+        // 1人用のモードの場合のみ
+        .add_systems(
+            Update,
+            (sprint_system.after(local_field_system), sprint_hud_system)
+                .run_if(resource_exists::<Sprint>)
+                .run_if(in_state(AppState::Playing).or_else(in_state(AppState::Finished))),
+        )
+        .add_systems(
+            Update,
+            (
+                ultra_system
+                    .after(handle_gameover)
+                    .run_if(in_state(AppState::Playing)),
+                ultra_hud_system
+                    .run_if(in_state(AppState::Playing).or_else(in_state(AppState::Finished))),
+            )
+                .run_if(resource_exists::<Ultra>),
+        )
+        .add_systems(
+            Update,
+            (
+                marathon_system
+                    .after(handle_gameover)
+                    .run_if(in_state(AppState::Playing)),
+                marathon_hud_system
+                    .run_if(in_state(AppState::Playing).or_else(in_state(AppState::Finished))),
+            )
+                .run_if(resource_exists::<Marathon>),
+        )
+        .add_systems(
+            Update,
+            (
+                dig_system
+                    .after(local_field_system)
+                    .after(handle_gameover)
+                    .run_if(in_state(AppState::Playing)),
+                dig_hud_system
+                    .run_if(in_state(AppState::Playing).or_else(in_state(AppState::Finished))),
+            )
+                .run_if(resource_exists::<Dig>),
+        )
         .run();
 }
 
 fn setup(mut commands: Commands) {
     commands.spawn(Camera2dBundle::default());
-    commands.spawn(SpriteBundle {
-        sprite: Sprite {
-            color: Color::WHITE,
-            custom_size: Some(Vec2::new(WINDOW_WIDTH, WINDOW_HEIGHT)),
+    commands.spawn((
+        Background,
+        SpriteBundle {
+            sprite: Sprite {
+                color: Color::WHITE,
+                custom_size: Some(Vec2::new(WINDOW_WIDTH, WINDOW_HEIGHT)),
+                ..default()
+            },
             ..default()
         },
-        ..default()
-    });
+    ));
 }
 
 fn camera_system(
     mut resize_events: EventReader<WindowResized>,
+    settings: Option<Res<CurrentMatch>>,
     window_query: Query<&Window>,
     mut projection_query: Query<&mut OrthographicProjection, With<Camera>>,
+    mut background_query: Query<&mut Transform, With<Background>>,
 ) {
-    for _ in resize_events.read() {
-        let Ok(window) = window_query.get_single() else {
-            return;
-        };
-        let Ok(mut projection) = projection_query.get_single_mut() else {
-            return;
-        };
-
-        let window_aspect = window.width() / window.height();
-        if window_aspect > WINDOW_ASPECT {
-            projection.scaling_mode = ScalingMode::FixedVertical(WINDOW_HEIGHT);
-        } else {
-            projection.scaling_mode = ScalingMode::FixedHorizontal(WINDOW_WIDTH);
-        }
+    let is_settings_changed = settings
+        .as_ref()
+        .is_some_and(|settings| settings.is_changed());
+    if resize_events.read().count() == 0 && !is_settings_changed {
+        return;
     }
-}
 
-fn setup_game(
-    mut field_query: Query<&mut LocalField>,
-    mut spawn_mino_events: EventWriter<SpawnMinoEvent>,
-) {
-    let Ok(mut field) = field_query.get_single_mut() else {
+    let Ok(window) = window_query.get_single() else {
         return;
     };
-    spawn_mino_events.send(SpawnMinoEvent(field.next_queue.pop()));
+    let Ok(mut projection) = projection_query.get_single_mut() else {
+        return;
+    };
+
+    // 大きなフィールドが収まるように表示する範囲を広げる
+    let scale = settings.map_or(1.0, |settings| {
+        let view_size = settings.rules.field_size.view_size();
+        (view_size.x / WINDOW_WIDTH)
+            .max(view_size.y / WINDOW_HEIGHT)
+            .max(1.0)
+    });
+
+    let window_aspect = window.width() / window.height();
+    if window_aspect > WINDOW_ASPECT {
+        projection.scaling_mode = ScalingMode::FixedVertical(WINDOW_HEIGHT * scale);
+    } else {
+        projection.scaling_mode = ScalingMode::FixedHorizontal(WINDOW_WIDTH * scale);
+    }
+    for mut transform in &mut background_query {
+        transform.scale = Vec3::splat(scale);
+    }
 }
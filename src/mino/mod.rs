@@ -1,21 +1,23 @@
 pub mod event;
 pub mod shape;
-pub mod t_spin;
+pub mod spin;
 
 use self::shape::Shape;
 use crate::{
-    field::{Field, FIELD_HEIGHT, FIELD_WIDTH},
+    field::blocks::Blocks,
+    movement::{get_new_angle, Rotation},
     pos,
     position::Position,
+    rotation::{bottom, RotationSystemKind},
 };
-use bevy::prelude::*;
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Copy, Component, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct Mino {
     pub pos: Position,
     pub angle: Angle,
     pub shape: Shape,
+    pub rotation_system: RotationSystemKind,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
@@ -28,31 +30,78 @@ pub enum Angle {
 }
 
 impl Mino {
-    pub fn new(shape: Shape, field: &Field) -> Option<Self> {
+    pub fn new(shape: Shape, blocks: &Blocks, rotation_system: RotationSystemKind) -> Option<Self> {
+        let angle = rotation_system.get().spawn_angle(shape);
+        let bottom = bottom(rotation_system.get().blocks(shape, angle));
+        let size = blocks.size();
+
         (0..=2)
             .rev()
-            .map(|offset_y| {
-                pos!(
-                    (FIELD_WIDTH - shape.width()) / 2,
-                    FIELD_HEIGHT - offset_y - shape.offset_y(),
-                )
-            })
-            .find(|&pos| field.blocks.can_place_mino(pos, shape, Angle::default()))
-            .map(|pos| Self {
-                pos,
-                angle: Angle::default(),
+            .map(|offset_y| Self {
+                pos: pos!(
+                    (size.width - shape.width()) / 2,
+                    size.height - offset_y - bottom,
+                ),
+                angle,
                 shape,
+                rotation_system,
             })
+            .find(|mino| blocks.can_place_mino(mino))
+    }
+
+    // フィールド上でのブロックの位置
+    pub fn positions(&self) -> impl Iterator<Item = Position> {
+        let mino_pos = self.pos;
+
+        self.rotation_system
+            .get()
+            .blocks(self.shape, self.angle)
+            .iter()
+            .map(move |&pos| pos + mino_pos)
+    }
+
+    // 回転補正を含めて回転したミノと，使われた回転補正
+    // どの補正でも置けない場合はNone
+    pub fn rotated(&self, rotation: Rotation, blocks: &Blocks) -> Option<(Self, Position)> {
+        let rotation_system = self.rotation_system.get();
+        let new_angle = get_new_angle(self.angle, rotation);
+        let rotated = Self {
+            angle: new_angle,
+            ..*self
+        };
+
+        let kicks = rotation_system.kicks(self.shape, self.angle, new_angle);
+        let kicks = if rotation_system.can_kick(self, new_angle, blocks) {
+            kicks
+        } else {
+            &kicks[..1]
+        };
+
+        kicks
+            .iter()
+            .map(|&delta| (rotated.moved(delta), delta))
+            .find(|(mino, _)| blocks.can_place_mino(mino))
     }
 
-    pub fn spawn(self, commands: &mut Commands) -> Entity {
-        commands.spawn((SpatialBundle::default(), self)).id()
+    pub fn moved(&self, delta: Position) -> Self {
+        Self {
+            pos: self.pos + delta,
+            ..*self
+        }
     }
 
-    pub fn is_landed(&self, field: &Field) -> bool {
-        !field
-            .blocks
-            .can_place_mino(self.pos + pos!(0, -1), self.shape, self.angle)
+    pub fn is_landed(&self, blocks: &Blocks) -> bool {
+        !blocks.can_place_mino(&self.moved(pos!(0, -1)))
+    }
+
+    // ハードドロップした場合に着地する位置のミノ
+    pub fn hard_dropped(&self, blocks: &Blocks) -> Self {
+        let mut mino = *self;
+        while !mino.is_landed(blocks) {
+            mino.pos += pos!(0, -1);
+        }
+
+        mino
     }
 }
 
@@ -0,0 +1,256 @@
+use crate::{field::blocks::Blocks, movement::Rotation, pos, position::Position};
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+
+use super::{shape::Shape, Mino};
+
+// 回転によって入れたミノ
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Spin {
+    #[default]
+    None,
+    Mini(Shape),
+    Full(Shape),
+}
+
+// どのミノのスピンを認めるか
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize, ValueEnum)]
+pub enum SpinRule {
+    // Tミノのみ
+    #[default]
+    TSpin,
+    // Tミノ以外も動けない位置に回転で入れるとスピン
+    AllSpin,
+    // AllSpinと同じだが，Tミノ以外は全てMini扱い
+    AllMini,
+}
+
+impl Spin {
+    pub fn update(
+        &mut self,
+        mino: &Mino,
+        blocks: &Blocks,
+        delta: Position,
+        rotation: Rotation,
+        rule: SpinRule,
+    ) {
+        *self = match mino.shape {
+            Shape::T => {
+                if Self::is_t_spin(mino, blocks) {
+                    if Self::is_t_spin_mini(mino, blocks, delta, rotation) {
+                        Self::Mini(mino.shape)
+                    } else {
+                        Self::Full(mino.shape)
+                    }
+                } else if rule != SpinRule::TSpin && Self::is_immobile(mino, blocks) {
+                    // 四隅の条件を満たさなくても，動けなければMini
+                    Self::Mini(mino.shape)
+                } else {
+                    Self::None
+                }
+            }
+            // Oミノは回転しても形が変わらないため対象外
+            Shape::O => Self::None,
+            shape => match rule {
+                SpinRule::TSpin => Self::None,
+                SpinRule::AllSpin if Self::is_immobile(mino, blocks) => Self::Full(shape),
+                SpinRule::AllMini if Self::is_immobile(mino, blocks) => Self::Mini(shape),
+                SpinRule::AllSpin | SpinRule::AllMini => Self::None,
+            },
+        };
+    }
+
+    pub fn is_none(self) -> bool {
+        self == Self::None
+    }
+
+    // 回転した後に左右と上のどこにも動けない
+    fn is_immobile(mino: &Mino, blocks: &Blocks) -> bool {
+        IMMOBILE_CHECK_DELTAS
+            .iter()
+            .all(|&delta| !blocks.can_place_mino(&mino.moved(delta)))
+    }
+
+    // 四隅が3箇所以上埋まっているとT-Spin
+    // 壁や床は埋まっている扱い
+    fn is_t_spin(mino: &Mino, blocks: &Blocks) -> bool {
+        let (center, _) = center_and_direction(mino);
+        let fullfilled = T_SPIN_CHECK_POSITIONS
+            .iter()
+            .map(|&pos| pos + center)
+            .filter(|&pos| blocks.get(pos).is_none_or(|block| block.is_filled()))
+            .count();
+
+        fullfilled >= 3
+    }
+
+    // T-Spinであり，90度回転の回転補正が(±1, ±2)ではなく，Tミノの凸側の隅2箇所が埋まっていないとT-Spin Mini
+    // 180度回転の補正には(±1, ±2)が通常の候補として含まれるため，例外として扱わない
+    fn is_t_spin_mini(mino: &Mino, blocks: &Blocks, delta: Position, rotation: Rotation) -> bool {
+        if rotation != Rotation::Half && delta.x.abs() == 1 && delta.y.abs() == 2 {
+            false
+        } else {
+            let (center, direction) = center_and_direction(mino);
+            let side = pos!(direction.y, direction.x);
+
+            ![center + direction + side, center + direction - side]
+                .into_iter()
+                .all(|pos| blocks.get(pos).is_none_or(|block| block.is_filled()))
+        }
+    }
+}
+
+// Tミノの中心のブロックの位置と，中心から見た凸側の向き
+// 回転法則によってミノ内での中心の位置が異なるため，ブロックの配置から求める
+fn center_and_direction(mino: &Mino) -> (Position, Position) {
+    let positions = mino.positions().collect::<Vec<_>>();
+
+    let center = *positions
+        .iter()
+        .find(|&&pos| {
+            positions
+                .iter()
+                .filter(|&&other| {
+                    let delta = other - pos;
+                    delta.x.abs() + delta.y.abs() == 1
+                })
+                .count()
+                == 3
+        })
+        .unwrap();
+    // 左右(上下)の腕は打ち消し合うため，残るのは凸側の向き
+    let direction = positions
+        .iter()
+        .fold(pos!(0, 0), |direction, &pos| direction + (pos - center));
+
+    (center, direction)
+}
+
+static T_SPIN_CHECK_POSITIONS: [Position; 4] = pos![(-1, -1), (1, -1), (-1, 1), (1, 1)];
+
+static IMMOBILE_CHECK_DELTAS: [Position; 3] = pos![(-1, 0), (1, 0), (0, 1)];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{field::block::Block, mino::Angle, rotation::RotationSystemKind};
+
+    // 上の行から順に並べた盤面．#が埋まっているブロック
+    fn blocks_from(rows: &[&str]) -> Blocks {
+        let mut blocks = Blocks::default();
+        for (y, row) in rows.iter().rev().enumerate() {
+            for (x, c) in row.chars().enumerate() {
+                if c == '#' {
+                    *blocks.get_mut(pos!(x as i8, y as i8)).unwrap() = Block::Garbage;
+                }
+            }
+        }
+        blocks
+    }
+
+    fn mino(shape: Shape, angle: Angle, x: i8, y: i8) -> Mino {
+        Mino {
+            pos: pos!(x, y),
+            angle,
+            shape,
+            rotation_system: RotationSystemKind::Srs,
+        }
+    }
+
+    fn spin(mino: &Mino, blocks: &Blocks, delta: Position, rule: SpinRule) -> Spin {
+        assert!(blocks.can_place_mino(mino));
+        let mut spin = Spin::None;
+        spin.update(mino, blocks, delta, Rotation::Right, rule);
+        spin
+    }
+
+    const RULES: [SpinRule; 3] = [SpinRule::TSpin, SpinRule::AllSpin, SpinRule::AllMini];
+
+    #[test]
+    fn t_spin_double_is_full() {
+        let blocks = blocks_from(&["...#......", "###...####", "####.#####"]);
+        let t = mino(Shape::T, Angle::Deg180, 3, 0);
+
+        for rule in RULES {
+            assert_eq!(spin(&t, &blocks, pos!(0, 0), rule), Spin::Full(Shape::T));
+        }
+    }
+
+    #[test]
+    fn t_spin_with_open_front_corner_is_mini() {
+        let blocks = blocks_from(&["...#......", ".........."]);
+        let t = mino(Shape::T, Angle::Deg0, 3, -1);
+
+        assert_eq!(
+            spin(&t, &blocks, pos!(0, 0), SpinRule::TSpin),
+            Spin::Mini(Shape::T)
+        );
+        // 90度回転で(±1, ±2)の補正を使った場合はMiniにならない
+        assert_eq!(
+            spin(&t, &blocks, pos!(-1, -2), SpinRule::TSpin),
+            Spin::Full(Shape::T)
+        );
+    }
+
+    #[test]
+    fn t_on_open_floor_is_not_spin() {
+        let blocks = Blocks::default();
+        let t = mino(Shape::T, Angle::Deg0, 3, -1);
+
+        for rule in RULES {
+            assert_eq!(spin(&t, &blocks, pos!(0, 0), rule), Spin::None);
+        }
+    }
+
+    #[test]
+    fn immobile_t_without_corners_is_mini_except_t_spin_rule() {
+        let blocks = blocks_from(&["....#.....", "..........", "..#...#..."]);
+        let t = mino(Shape::T, Angle::Deg0, 3, -1);
+
+        assert_eq!(spin(&t, &blocks, pos!(0, 0), SpinRule::TSpin), Spin::None);
+        assert_eq!(
+            spin(&t, &blocks, pos!(0, 0), SpinRule::AllSpin),
+            Spin::Mini(Shape::T)
+        );
+        assert_eq!(
+            spin(&t, &blocks, pos!(0, 0), SpinRule::AllMini),
+            Spin::Mini(Shape::T)
+        );
+    }
+
+    #[test]
+    fn immobile_other_piece_depends_on_rule() {
+        let blocks = blocks_from(&["...#......", "###....###"]);
+        let i = mino(Shape::I, Angle::Deg0, 3, -2);
+
+        assert_eq!(spin(&i, &blocks, pos!(0, 0), SpinRule::TSpin), Spin::None);
+        assert_eq!(
+            spin(&i, &blocks, pos!(0, 0), SpinRule::AllSpin),
+            Spin::Full(Shape::I)
+        );
+        assert_eq!(
+            spin(&i, &blocks, pos!(0, 0), SpinRule::AllMini),
+            Spin::Mini(Shape::I)
+        );
+    }
+
+    #[test]
+    fn mobile_other_piece_is_not_spin() {
+        let blocks = blocks_from(&["###....###"]);
+        let i = mino(Shape::I, Angle::Deg0, 3, -2);
+
+        for rule in RULES {
+            assert_eq!(spin(&i, &blocks, pos!(0, 0), rule), Spin::None);
+        }
+    }
+
+    #[test]
+    fn o_never_spins() {
+        let blocks = blocks_from(&["....#.....", "...#..#...", "...#..#..."]);
+        let o = mino(Shape::O, Angle::Deg0, 4, 0);
+
+        for rule in RULES {
+            assert_eq!(spin(&o, &blocks, pos!(0, 0), rule), Spin::None);
+        }
+    }
+}
@@ -0,0 +1,61 @@
+use crate::state::AppState;
+use bevy::prelude::*;
+use std::time::Duration;
+
+const COUNTDOWN_DURATION: Duration = Duration::from_secs(3);
+
+static COUNTDOWN_TEXT_SIZE: f32 = 160.0;
+static COUNTDOWN_TEXT_COLOR: Color = Color::rgb(0.2, 0.2, 0.2);
+
+// 1人用のモードを始める前の待ち時間
+#[derive(Resource)]
+pub struct Countdown(Timer);
+
+#[derive(Component)]
+pub struct CountdownText;
+
+pub fn setup_countdown(mut commands: Commands) {
+    commands.insert_resource(Countdown(Timer::new(COUNTDOWN_DURATION, TimerMode::Once)));
+    commands.spawn((
+        CountdownText,
+        Text2dBundle {
+            // フィールドのブロックより手前に表示する
+            transform: Transform::from_translation(Vec3::new(0.0, 0.0, 10.0)),
+            text: Text::from_section(
+                "",
+                TextStyle {
+                    font_size: COUNTDOWN_TEXT_SIZE,
+                    color: COUNTDOWN_TEXT_COLOR,
+                    ..default()
+                },
+            ),
+            ..default()
+        },
+    ));
+}
+
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+pub fn countdown_system(
+    time: Res<Time>,
+    mut countdown: ResMut<Countdown>,
+    mut text_query: Query<&mut Text, With<CountdownText>>,
+    mut app_state: ResMut<NextState<AppState>>,
+) {
+    if countdown.0.tick(time.delta()).finished() {
+        app_state.set(AppState::Playing);
+        return;
+    }
+
+    let Ok(mut text) = text_query.get_single_mut() else {
+        return;
+    };
+    let seconds = countdown.0.remaining().as_secs_f32().ceil() as u32;
+    text.sections[0].value = seconds.to_string();
+}
+
+pub fn cleanup_countdown(mut commands: Commands, text_query: Query<Entity, With<CountdownText>>) {
+    for entity in &text_query {
+        commands.entity(entity).despawn_recursive();
+    }
+    commands.remove_resource::<Countdown>();
+}
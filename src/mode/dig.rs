@@ -0,0 +1,119 @@
+use super::{format_time, new_record_text, records::Records, ModeHud};
+use crate::{
+    args::Args,
+    field::{local::LocalField, stats::Stats, Field},
+    game::rules::FieldSize,
+    net::PlayerState,
+    state::{AppState, GameOverEvent},
+};
+use bevy::prelude::*;
+use std::time::Duration;
+
+pub const DEFAULT_DIG_ROWS: u8 = 10;
+// ミノを出現させるために空けておく段数
+const DIG_SPAWN_ROWS: i8 = 4;
+// おじゃま行を1行以上積める高さ
+pub const MIN_DIG_FIELD_HEIGHT: i8 = DIG_SPAWN_ROWS + 1;
+// survivalでおじゃま行が増える間隔
+const DIG_SURVIVAL_INTERVAL: Duration = Duration::from_secs(2);
+
+#[derive(Resource)]
+pub struct Dig {
+    // 最初に積まれているおじゃま行の数
+    pub rows: u8,
+    // survivalの場合のみ，おじゃま行を増やす間隔
+    pub survival_timer: Option<Timer>,
+    // 始める前の自己ベスト．survivalの場合は最長の生存時間
+    pub best: Option<Duration>,
+    pub is_new_record: bool,
+}
+
+impl Dig {
+    pub fn new(rows: u8, is_survival: bool, records: &Records) -> Self {
+        let (survival_timer, best) = if is_survival {
+            (
+                Some(Timer::new(DIG_SURVIVAL_INTERVAL, TimerMode::Repeating)),
+                records.dig_survival,
+            )
+        } else {
+            (None, records.dig.get(&rows).copied())
+        };
+
+        Self {
+            rows,
+            survival_timer,
+            best,
+            is_new_record: false,
+        }
+    }
+
+    #[allow(clippy::cast_sign_loss)]
+    pub fn max_rows(size: FieldSize) -> u8 {
+        (size.height - DIG_SPAWN_ROWS).max(0) as u8
+    }
+}
+
+// 全てのおじゃま行を消したら終了する
+// survivalの場合は一定時間ごとにおじゃま行をせり上げ，ゲームオーバーになったら終了する
+pub fn dig_system(
+    time: Res<Time>,
+    args: Res<Args>,
+    mut dig: ResMut<Dig>,
+    mut records: ResMut<Records>,
+    mut app_state: ResMut<NextState<AppState>>,
+    mut gameover_events: EventWriter<GameOverEvent>,
+    mut field_query: Query<(&mut Field, &mut LocalField, &Stats)>,
+) {
+    let Ok((mut field, mut local_field, stats)) = field_query.get_single_mut() else {
+        return;
+    };
+    let is_playing = field.player.state == PlayerState::Playing;
+
+    dig.is_new_record = if let Some(survival_timer) = &mut dig.survival_timer {
+        if is_playing {
+            // 相殺できないように，おじゃま行の列を通さずにせり上げる
+            let rows = survival_timer.tick(time.delta()).times_finished_this_tick();
+            if rows > 0 {
+                if let Err(top_out) = local_field.game.add_cheese(rows as u8) {
+                    gameover_events.send(GameOverEvent(top_out));
+                }
+            }
+            return;
+        }
+
+        records.update_dig_survival(stats.elapsed)
+    } else {
+        if !is_playing || local_field.game.blocks.count_garbage_rows() > 0 {
+            return;
+        }
+
+        field.player.state = PlayerState::Completed;
+        app_state.set(AppState::Finished);
+        records.update_dig(dig.rows, stats.elapsed)
+    };
+
+    if dig.is_new_record {
+        records.save(&args.records);
+    }
+}
+
+pub fn dig_hud_system(
+    dig: Res<Dig>,
+    mut hud_query: Query<(&mut Text, &Parent), With<ModeHud>>,
+    field_query: Query<(&LocalField, &Stats)>,
+) {
+    for (mut text, parent) in &mut hud_query {
+        let Ok((local_field, stats)) = field_query.get(parent.get()) else {
+            continue;
+        };
+
+        let rows = local_field.game.blocks.count_garbage_rows();
+        let best = dig.best.map_or_else(|| "-".to_string(), format_time);
+        text.sections[0].value = format!(
+            "GARBAGE\n{rows}\nTIME\n{}\nPIECES\n{}\nBEST\n{best}{}",
+            format_time(stats.elapsed),
+            stats.pieces,
+            new_record_text(dig.is_new_record),
+        );
+    }
+}
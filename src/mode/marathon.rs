@@ -0,0 +1,87 @@
+use super::{format_time, new_record_text, records::Records, ModeHud};
+use crate::{
+    args::Args,
+    field::{local::LocalField, stats::Stats, Field},
+    net::PlayerState,
+    state::AppState,
+};
+use bevy::prelude::*;
+
+pub const MARATHON_LINES: u32 = 150;
+
+#[derive(Resource)]
+pub struct Marathon {
+    // 目標のライン数．エンドレスの場合はNone
+    pub lines: Option<u32>,
+    // 始める前の最高得点
+    pub best: Option<u64>,
+    pub is_new_record: bool,
+}
+
+impl Marathon {
+    pub fn new(is_endless: bool, records: &Records) -> Self {
+        let (lines, best) = if is_endless {
+            (None, records.endless_marathon)
+        } else {
+            (Some(MARATHON_LINES), records.marathon)
+        };
+
+        Self {
+            lines,
+            best,
+            is_new_record: false,
+        }
+    }
+}
+
+// 目標のライン数を消すかゲームオーバーになったら終了し，最高得点を更新する
+pub fn marathon_system(
+    args: Res<Args>,
+    mut marathon: ResMut<Marathon>,
+    mut records: ResMut<Records>,
+    mut app_state: ResMut<NextState<AppState>>,
+    mut field_query: Query<(&mut Field, &LocalField)>,
+) {
+    let Ok((mut field, local_field)) = field_query.get_single_mut() else {
+        return;
+    };
+    let score = &local_field.game.score;
+    match field.player.state {
+        PlayerState::Playing if marathon.lines.is_some_and(|lines| score.lines >= lines) => {
+            field.player.state = PlayerState::Completed;
+            app_state.set(AppState::Finished);
+        }
+        PlayerState::Playing => return,
+        _ => {}
+    }
+
+    marathon.is_new_record = records.update_marathon(marathon.lines.is_none(), score.points);
+    if marathon.is_new_record {
+        records.save(&args.records);
+    }
+}
+
+pub fn marathon_hud_system(
+    marathon: Res<Marathon>,
+    mut hud_query: Query<(&mut Text, &Parent), With<ModeHud>>,
+    field_query: Query<(&LocalField, &Stats)>,
+) {
+    for (mut text, parent) in &mut hud_query {
+        let Ok((local_field, stats)) = field_query.get(parent.get()) else {
+            continue;
+        };
+
+        let lines = local_field.game.score.lines;
+        let goal = marathon
+            .lines
+            .map_or_else(|| lines.to_string(), |goal| format!("{lines}/{goal}"));
+        let best = marathon
+            .best
+            .map_or_else(|| "-".to_string(), |best| best.to_string());
+        text.sections[0].value = format!(
+            "GOAL\n{goal}\nTIME\n{}\nBEST\n{best}{}",
+            format_time(stats.elapsed),
+            new_record_text(marathon.is_new_record),
+        );
+    }
+}
@@ -0,0 +1,161 @@
+pub mod countdown;
+pub mod dig;
+pub mod marathon;
+pub mod records;
+pub mod sprint;
+pub mod ultra;
+
+use self::{dig::Dig, marathon::Marathon, records::Records, sprint::Sprint, ultra::Ultra};
+use crate::{
+    args::Args,
+    field::{
+        finesse::FinesseChecker,
+        local::{next_pos, LocalField, LocalFieldBundle, NEXT_HOLD_BG_HEIGHT},
+        next::QUEUE_SIZE,
+        Field,
+    },
+    game::rules::{FieldSize, MatchSettings},
+    net::{CurrentMatch, Player},
+};
+use bevy::{prelude::*, sprite::Anchor};
+use clap::ValueEnum;
+use rand::random;
+use serde::Deserialize;
+use std::time::Duration;
+
+static MODE_HUD_TEXT_SIZE: f32 = 24.0;
+static MODE_HUD_TEXT_COLOR: Color = Color::rgb(0.2, 0.2, 0.2);
+
+// 遊び方．versus以外は通信せずに1人で遊ぶ
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize, ValueEnum)]
+pub enum GameMode {
+    #[default]
+    Versus,
+    // 決められたライン数を消すまでの時間を競う
+    Sprint,
+    // 制限時間内の得点を競う
+    Ultra,
+    // 消したライン数に応じて速くなる落下に耐える
+    Marathon,
+    // 最初に積まれたおじゃま行を掘り進める
+    Dig,
+}
+
+// モードごとの目標や記録．ネクストの下に表示する
+#[derive(Component)]
+pub struct ModeHud;
+
+impl GameMode {
+    pub fn is_solo(self) -> bool {
+        self != Self::Versus
+    }
+}
+
+impl ModeHud {
+    pub fn spawn(parent: &mut ChildBuilder, size: FieldSize) {
+        let translation =
+            next_pos(QUEUE_SIZE, size) + Vec3::new(0.0, NEXT_HOLD_BG_HEIGHT / 2.0, 1.0);
+
+        parent.spawn((
+            ModeHud,
+            Text2dBundle {
+                transform: Transform::from_translation(translation),
+                text: Text::from_section(
+                    "",
+                    TextStyle {
+                        font_size: MODE_HUD_TEXT_SIZE,
+                        color: MODE_HUD_TEXT_COLOR,
+                        ..default()
+                    },
+                )
+                .with_justify(JustifyText::Center),
+                text_anchor: Anchor::TopCenter,
+                ..default()
+            },
+        ));
+    }
+}
+
+// 通信せずに，自分のフィールドだけを中央に置く
+// versusの場合は目標の無いフリープレイになる
+pub fn setup_offline_system(mut commands: Commands, args: Res<Args>) {
+    let settings = MatchSettings {
+        seed: args.seed.unwrap_or_else(random),
+        rules: args.rules(),
+    };
+
+    Field::new(Player::local(), settings.rules.field_size).spawn(
+        &mut commands,
+        Some(LocalFieldBundle::new(settings.clone())),
+        Transform::default(),
+    );
+    commands.insert_resource(CurrentMatch(settings));
+}
+
+// 自分のフィールドにモードの表示を加え，目標と自己ベストを用意する
+pub fn setup_mode_system(
+    mut commands: Commands,
+    args: Res<Args>,
+    settings: Res<CurrentMatch>,
+    mut field_query: Query<(Entity, &mut Field, &mut LocalField)>,
+) {
+    let Ok((field_entity, mut field, mut local_field)) = field_query.get_single_mut() else {
+        return;
+    };
+    let size = settings.rules.field_size;
+    let records = Records::load(&args.records);
+
+    let dig_rows = args.dig_rows.min(Dig::max_rows(size));
+    if args.mode == GameMode::Dig {
+        let game = &mut local_field.game;
+        // フィールドに収まる数に減らしているため，ゲームオーバーにはならない
+        // 高さはArgs::validateで調べているため，1行以上は積まれる
+        game.add_cheese(dig_rows)
+            .expect("dig rows are clamped to fit in the field");
+        // カウントダウン中もおじゃま行を表示する
+        field.blocks = game.blocks.clone();
+    }
+
+    commands
+        .entity(field_entity)
+        // 結果に表示するため，警告を表示しない場合も操作の無駄を調べる
+        .insert(FinesseChecker::default())
+        .with_children(|parent| ModeHud::spawn(parent, size));
+
+    match args.mode {
+        GameMode::Versus => {}
+        GameMode::Sprint => {
+            commands.insert_resource(Sprint::new(args.sprint_lines, &records));
+        }
+        GameMode::Ultra => {
+            commands.insert_resource(Ultra::new(args.ultra_minutes, &records));
+        }
+        GameMode::Marathon => {
+            commands.insert_resource(Marathon::new(args.marathon_endless, &records));
+        }
+        GameMode::Dig => {
+            commands.insert_resource(Dig::new(dig_rows, args.dig_survival, &records));
+        }
+    }
+
+    commands.insert_resource(records);
+}
+
+fn new_record_text(is_new_record: bool) -> &'static str {
+    if is_new_record {
+        "\nNEW RECORD!"
+    } else {
+        ""
+    }
+}
+
+// 分:秒.ミリ秒
+pub fn format_time(time: Duration) -> String {
+    let millis = time.as_millis();
+    format!(
+        "{}:{:02}.{:03}",
+        millis / 60_000,
+        millis / 1000 % 60,
+        millis % 1000
+    )
+}
@@ -0,0 +1,92 @@
+use bevy::prelude::*;
+use ron::ser::PrettyConfig;
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, fs, path::Path, time::Duration};
+
+// 1人用のモードの自己ベスト
+#[derive(Debug, Default, Serialize, Deserialize, Resource)]
+#[serde(default)]
+pub struct Records {
+    // 目標のライン数ごとの最速タイム
+    pub sprint: BTreeMap<u32, Duration>,
+    // 制限時間(分)ごとの最高得点
+    pub ultra: BTreeMap<u64, u64>,
+    // 最高得点
+    pub marathon: Option<u64>,
+    pub endless_marathon: Option<u64>,
+    // 最初のおじゃま行の数ごとの最速タイム
+    pub dig: BTreeMap<u8, Duration>,
+    // 最長の生存時間
+    pub dig_survival: Option<Duration>,
+}
+
+impl Records {
+    // ファイルが無いか読めない場合は記録なしとする
+    pub fn load(path: &Path) -> Self {
+        fs::read_to_string(path)
+            .ok()
+            .and_then(|source| ron::from_str(&source).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, path: &Path) {
+        let result = ron::ser::to_string_pretty(self, PrettyConfig::default())
+            .map_err(|err| err.to_string())
+            .and_then(|source| fs::write(path, source).map_err(|err| err.to_string()));
+
+        if let Err(err) = result {
+            warn!("Failed to save records to {}: {}", path.display(), err);
+        }
+    }
+
+    // 記録を更新した場合はtrue
+    pub fn update_sprint(&mut self, lines: u32, time: Duration) -> bool {
+        if self.sprint.get(&lines).is_some_and(|&best| best <= time) {
+            return false;
+        }
+
+        self.sprint.insert(lines, time);
+        true
+    }
+
+    pub fn update_ultra(&mut self, minutes: u64, score: u64) -> bool {
+        if self.ultra.get(&minutes).is_some_and(|&best| best >= score) {
+            return false;
+        }
+
+        self.ultra.insert(minutes, score);
+        true
+    }
+
+    pub fn update_dig(&mut self, rows: u8, time: Duration) -> bool {
+        if self.dig.get(&rows).is_some_and(|&best| best <= time) {
+            return false;
+        }
+
+        self.dig.insert(rows, time);
+        true
+    }
+
+    pub fn update_dig_survival(&mut self, time: Duration) -> bool {
+        if self.dig_survival.is_some_and(|best| best >= time) {
+            return false;
+        }
+
+        self.dig_survival = Some(time);
+        true
+    }
+
+    pub fn update_marathon(&mut self, is_endless: bool, score: u64) -> bool {
+        let best = if is_endless {
+            &mut self.endless_marathon
+        } else {
+            &mut self.marathon
+        };
+        if best.is_some_and(|best| best >= score) {
+            return false;
+        }
+
+        *best = Some(score);
+        true
+    }
+}
@@ -0,0 +1,74 @@
+use super::{format_time, new_record_text, records::Records, ModeHud};
+use crate::{
+    args::Args,
+    field::{local::LocalField, stats::Stats, Field},
+    net::PlayerState,
+    state::AppState,
+};
+use bevy::prelude::*;
+use std::time::Duration;
+
+pub const DEFAULT_SPRINT_LINES: u32 = 40;
+
+#[derive(Resource)]
+pub struct Sprint {
+    // 目標のライン数
+    pub lines: u32,
+    // 始める前の自己ベスト
+    pub best: Option<Duration>,
+    pub is_new_record: bool,
+}
+
+impl Sprint {
+    pub fn new(lines: u32, records: &Records) -> Self {
+        Self {
+            lines,
+            best: records.sprint.get(&lines).copied(),
+            is_new_record: false,
+        }
+    }
+}
+
+// 目標のライン数を消したら終了し，自己ベストを更新する
+pub fn sprint_system(
+    args: Res<Args>,
+    mut sprint: ResMut<Sprint>,
+    mut records: ResMut<Records>,
+    mut app_state: ResMut<NextState<AppState>>,
+    mut field_query: Query<(&mut Field, &LocalField, &Stats)>,
+) {
+    let Ok((mut field, local_field, stats)) = field_query.get_single_mut() else {
+        return;
+    };
+    if field.player.state != PlayerState::Playing || local_field.game.score.lines < sprint.lines {
+        return;
+    }
+
+    field.player.state = PlayerState::Completed;
+    sprint.is_new_record = records.update_sprint(sprint.lines, stats.elapsed);
+    if sprint.is_new_record {
+        records.save(&args.records);
+    }
+
+    app_state.set(AppState::Finished);
+}
+
+pub fn sprint_hud_system(
+    sprint: Res<Sprint>,
+    mut hud_query: Query<(&mut Text, &Parent), With<ModeHud>>,
+    field_query: Query<(&LocalField, &Stats)>,
+) {
+    for (mut text, parent) in &mut hud_query {
+        let Ok((local_field, stats)) = field_query.get(parent.get()) else {
+            continue;
+        };
+
+        let left = sprint.lines.saturating_sub(local_field.game.score.lines);
+        let best = sprint.best.map_or_else(|| "-".to_string(), format_time);
+        text.sections[0].value = format!(
+            "LEFT\n{left}\nTIME\n{}\nBEST\n{best}{}",
+            format_time(stats.elapsed),
+            new_record_text(sprint.is_new_record),
+        );
+    }
+}
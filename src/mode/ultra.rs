@@ -0,0 +1,81 @@
+use super::{format_time, new_record_text, records::Records, ModeHud};
+use crate::{
+    args::Args,
+    field::{local::LocalField, stats::Stats, Field},
+    net::PlayerState,
+    state::AppState,
+};
+use bevy::prelude::*;
+use std::time::Duration;
+
+pub const DEFAULT_ULTRA_MINUTES: u64 = 3;
+
+#[derive(Resource)]
+pub struct Ultra {
+    pub minutes: u64,
+    // 始める前の最高得点
+    pub best: Option<u64>,
+    pub is_new_record: bool,
+}
+
+impl Ultra {
+    pub fn new(minutes: u64, records: &Records) -> Self {
+        Self {
+            minutes,
+            best: records.ultra.get(&minutes).copied(),
+            is_new_record: false,
+        }
+    }
+
+    pub fn time_limit(&self) -> Duration {
+        Duration::from_secs(self.minutes * 60)
+    }
+}
+
+// 制限時間が過ぎるかゲームオーバーになったら終了し，最高得点を更新する
+pub fn ultra_system(
+    args: Res<Args>,
+    mut ultra: ResMut<Ultra>,
+    mut records: ResMut<Records>,
+    mut app_state: ResMut<NextState<AppState>>,
+    mut field_query: Query<(&mut Field, &LocalField, &Stats)>,
+) {
+    let Ok((mut field, local_field, stats)) = field_query.get_single_mut() else {
+        return;
+    };
+    match field.player.state {
+        PlayerState::Playing if stats.elapsed >= ultra.time_limit() => {
+            field.player.state = PlayerState::Completed;
+            app_state.set(AppState::Finished);
+        }
+        PlayerState::Playing => return,
+        _ => {}
+    }
+
+    ultra.is_new_record = records.update_ultra(ultra.minutes, local_field.game.score.points);
+    if ultra.is_new_record {
+        records.save(&args.records);
+    }
+}
+
+pub fn ultra_hud_system(
+    ultra: Res<Ultra>,
+    mut hud_query: Query<(&mut Text, &Parent), With<ModeHud>>,
+    field_query: Query<&Stats>,
+) {
+    for (mut text, parent) in &mut hud_query {
+        let Ok(stats) = field_query.get(parent.get()) else {
+            continue;
+        };
+
+        let left = ultra.time_limit().saturating_sub(stats.elapsed);
+        let best = ultra
+            .best
+            .map_or_else(|| "-".to_string(), |best| best.to_string());
+        text.sections[0].value = format!(
+            "TIME\n{}\nBEST\n{best}{}",
+            format_time(left),
+            new_record_text(ultra.is_new_record),
+        );
+    }
+}
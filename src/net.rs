@@ -2,55 +2,83 @@ use crate::{
     args::Args,
     field::{
         blocks::{Garbages, Lines},
-        local::ReceiveGarbageEvent,
+        finesse::FinesseChecker,
+        local::{LocalFieldBundle, ReceiveGarbageEvent},
+        target::TargetChangeEvent,
         Field,
     },
+    game::{rules::MatchSettings, ClearType, TopOut},
     mino::{event::SyncFieldChangeEvent, Mino},
     state::StateChangeEvent,
     AppState,
 };
-use bevy::prelude::*;
+use bevy::{prelude::*, utils::Uuid};
 use bevy_matchbox::prelude::*;
+use rand::random;
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub struct PlayerId(PeerId);
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
 pub enum PlayerState {
     #[default]
     Playing,
-    GameOver,
+    GameOver(TopOut),
     Win,
+    // 1人用のモードで目標を達成した
+    Completed,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Player {
     pub id: PlayerId,
     pub state: PlayerState,
+    // このプレイヤーが狙っている相手
+    pub target: Option<PlayerId>,
 }
 
 #[derive(Resource)]
 pub struct Players(pub Vec<Player>);
 
+// 今の試合の設定
+#[derive(Resource, Deref)]
+pub struct CurrentMatch(pub MatchSettings);
+
 impl Player {
     fn new(peer_id: PeerId) -> Self {
         Self {
             id: PlayerId(peer_id),
             state: PlayerState::default(),
+            target: None,
         }
     }
+
+    // 通信せずに1人で遊ぶ場合のプレイヤー
+    pub fn local() -> Self {
+        Self::new(PeerId(Uuid::nil()))
+    }
 }
 
 #[derive(Resource)]
 pub struct Socket(MatchboxSocket<SingleChannel>);
 
+// マッチングを待っている間に届いた，試合が始まってから処理するメッセージ
+#[derive(Resource, Default)]
+pub struct PendingMessages(Vec<(PeerId, Box<[u8]>)>);
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 enum Message {
+    MatchStarted {
+        settings: MatchSettings,
+    },
     FieldChanged {
         mino: Mino,
         clear_lines: Lines,
+        clear_type: ClearType,
         garbage_lines: Garbages,
+        // 試合開始からキーを押した回数
+        keys: u32,
     },
     GarbageSent {
         amount: u8,
@@ -58,6 +86,9 @@ enum Message {
     StateChanged {
         state: PlayerState,
     },
+    TargetChanged {
+        target: Option<PlayerId>,
+    },
 }
 
 pub fn setup_matchbox_socket(mut commands: Commands, args: Res<Args>) {
@@ -70,13 +101,16 @@ pub fn setup_matchbox_socket(mut commands: Commands, args: Res<Args>) {
     let socket = MatchboxSocket::from(builer);
     let socket = Socket(socket);
     commands.insert_resource(socket);
+    commands.init_resource::<PendingMessages>();
 }
 
 pub fn waiting_for_player_system(
     mut commands: Commands,
     mut socket: ResMut<Socket>,
+    mut pending_messages: ResMut<PendingMessages>,
     mut app_state: ResMut<NextState<AppState>>,
     args: Res<Args>,
+    mut received_settings: Local<Option<MatchSettings>>,
 ) {
     let Socket(socket) = &mut *socket;
 
@@ -91,15 +125,58 @@ pub fn waiting_for_player_system(
         }
     }
 
+    for (peer_id, message) in socket.receive() {
+        if let Message::MatchStarted { settings } = bincode::deserialize(&message).unwrap() {
+            info!("{}: MatchStarted", peer_id);
+            *received_settings = Some(settings);
+        } else {
+            // 先に試合を始めたプレイヤーからのメッセージは捨てずに取っておく
+            pending_messages.0.push((peer_id, message));
+        }
+    }
+
     // 自分は数えないので，1つ減らす
     if socket.connected_peers().count() < args.players - 1 {
         return;
     }
 
+    // IDが最も小さいプレイヤーがホストとなり，試合の設定を決めて全員に送る
+    let my_id = socket.id().unwrap();
+    let peers = socket.connected_peers().collect::<Vec<_>>();
+    let settings = if peers.iter().all(|&peer| my_id < peer) {
+        let settings = MatchSettings {
+            seed: args.seed.unwrap_or_else(random),
+            rules: args.rules(),
+        };
+
+        let message = Message::MatchStarted {
+            settings: settings.clone(),
+        };
+        let message = bincode::serialize(&message).unwrap().into_boxed_slice();
+        for &peer in &peers {
+            socket.send(message.clone(), peer);
+        }
+
+        settings
+    } else if let Some(settings) = received_settings.take() {
+        settings
+    } else {
+        return;
+    };
+
     info!("All player has joined, starting game!");
 
-    let my_player = Player::new(socket.id().unwrap());
-    Field::new(my_player).spawn(&mut commands, true, Vec3::new(-350., 0., 0.));
+    let size = settings.rules.field_size;
+    let my_player = Player::new(my_id);
+    let my_field = Field::new(my_player, size).spawn(
+        &mut commands,
+        Some(LocalFieldBundle::new(settings.clone())),
+        Transform::from_xyz(size.local_field_x(), 0., 0.),
+    );
+    // 対戦では操作の無駄を調べない
+    if args.finesse && args.players == 1 {
+        commands.entity(my_field).insert(FinesseChecker::default());
+    }
 
     let mut players = socket
         .connected_peers()
@@ -107,42 +184,57 @@ pub fn waiting_for_player_system(
         .collect::<Vec<_>>();
     players.sort_by_key(|player| player.id);
 
-    for &player in players.iter() {
-        // TODO: 大人数でも正しく並べる
-        Field::new(player).spawn(&mut commands, false, Vec3::new(350., 0., 0.));
+    for (i, &player) in players.iter().enumerate() {
+        Field::new(player, size).spawn(
+            &mut commands,
+            None,
+            size.remote_field_transform(i, players.len()),
+        );
     }
 
     let players = Players(players);
     commands.insert_resource(players);
+    commands.insert_resource(CurrentMatch(settings));
     app_state.set(AppState::Playing);
 }
 
 pub fn receive_message_system(
     mut socket: ResMut<Socket>,
+    mut pending_messages: ResMut<PendingMessages>,
     mut receive_garbage_events: EventWriter<ReceiveGarbageEvent>,
     mut sync_field_change_events: EventWriter<SyncFieldChangeEvent>,
     mut state_change_events: EventWriter<StateChangeEvent>,
+    mut target_change_events: EventWriter<TargetChangeEvent>,
 ) {
     let Socket(socket) = &mut *socket;
 
-    for (peer_id, message) in socket.receive() {
+    // マッチング中に届いたメッセージを先に処理する
+    for (peer_id, message) in pending_messages.0.drain(..).chain(socket.receive()) {
         match bincode::deserialize(&message).unwrap() {
+            Message::MatchStarted { .. } => {}
             Message::FieldChanged {
                 mino,
                 clear_lines,
+                clear_type,
                 garbage_lines,
+                keys,
             } => {
                 info!("{}: FieldChanged", peer_id);
                 sync_field_change_events.send(SyncFieldChangeEvent {
                     player_id: PlayerId(peer_id),
                     mino,
                     clear_lines,
+                    clear_type,
                     garbage_lines,
+                    keys,
                 });
             }
             Message::GarbageSent { amount } => {
                 info!("{}: GarbageSent", peer_id);
-                receive_garbage_events.send(ReceiveGarbageEvent(amount));
+                receive_garbage_events.send(ReceiveGarbageEvent {
+                    player_id: PlayerId(peer_id),
+                    amount,
+                });
             }
             Message::StateChanged { state } => {
                 info!("{}: StageChanged", peer_id);
@@ -151,6 +243,13 @@ pub fn receive_message_system(
                     state,
                 });
             }
+            Message::TargetChanged { target } => {
+                info!("{}: TargetChanged", peer_id);
+                target_change_events.send(TargetChangeEvent {
+                    player_id: PlayerId(peer_id),
+                    target,
+                });
+            }
         }
     }
 }
@@ -171,17 +270,30 @@ pub fn broadcast_state(Socket(socket): &mut Socket, players: &Players, state: Pl
     }
 }
 
+pub fn broadcast_target(Socket(socket): &mut Socket, players: &Players, target: Option<PlayerId>) {
+    let message = Message::TargetChanged { target };
+    let message = bincode::serialize(&message).unwrap().into_boxed_slice();
+
+    for player in players.0.iter() {
+        socket.send(message.clone(), player.id.0);
+    }
+}
+
 pub fn sync_local_field_change(
     Socket(socket): &mut Socket,
     players: &Players,
     mino: Mino,
     clear_lines: Lines,
+    clear_type: ClearType,
     garbage_lines: Garbages,
+    keys: u32,
 ) {
     let message = Message::FieldChanged {
         mino,
         clear_lines,
+        clear_type,
         garbage_lines,
+        keys,
     };
     let message = bincode::serialize(&message).unwrap().into_boxed_slice();
 
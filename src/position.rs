@@ -1,9 +1,9 @@
-use crate::field::{block::BLOCK_SIZE, FIELD_HEIGHT, FIELD_WIDTH};
+use crate::{field::block::BLOCK_SIZE, game::rules::FieldSize};
 use bevy::prelude::*;
 use serde::{Deserialize, Serialize};
 use std::ops::{Add, AddAssign, Sub, SubAssign};
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct Position {
     pub x: i8,
     pub y: i8,
@@ -20,10 +20,11 @@ macro_rules! pos {
 }
 
 impl Position {
-    pub fn translation(self) -> Vec3 {
+    // フィールドの中心を原点とした，ブロックの左下の位置
+    pub fn translation(self, size: FieldSize) -> Vec3 {
         Vec3::new(
-            (self.x as f32 - FIELD_WIDTH as f32 / 2.) * BLOCK_SIZE,
-            (self.y as f32 - FIELD_HEIGHT as f32 / 2.) * BLOCK_SIZE,
+            (self.x as f32 - size.width as f32 / 2.) * BLOCK_SIZE,
+            (self.y as f32 - size.height as f32 / 2.) * BLOCK_SIZE,
             0.0,
         )
     }
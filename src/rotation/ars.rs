@@ -0,0 +1,100 @@
+use super::RotationSystem;
+use crate::{
+    field::blocks::Blocks,
+    mino::{shape::Shape, Angle, Mino},
+    pos,
+    position::Position,
+};
+
+// アリカ・ローテーションシステム(TGM)
+pub struct Ars;
+
+impl RotationSystem for Ars {
+    fn blocks(&self, shape: Shape, angle: Angle) -> &'static [Position] {
+        let angle_idx: usize = angle.into();
+
+        match shape {
+            Shape::I => &I_SHAPES[angle_idx],
+            Shape::J => &J_SHAPES[angle_idx],
+            Shape::L => &L_SHAPES[angle_idx],
+            Shape::O => &O_SHAPES[angle_idx],
+            Shape::S => &S_SHAPES[angle_idx],
+            Shape::T => &T_SHAPES[angle_idx],
+            Shape::Z => &Z_SHAPES[angle_idx],
+        }
+    }
+
+    // 左右に1マスずつ補正する．Iミノ，Oミノは補正しない
+    fn kicks(&self, shape: Shape, _angle: Angle, _new_angle: Angle) -> &'static [Position] {
+        match shape {
+            Shape::I | Shape::O => &ARS_DELTAS_I,
+            Shape::J | Shape::L | Shape::S | Shape::T | Shape::Z => &ARS_DELTAS,
+        }
+    }
+
+    // J，L，Tミノは，回転後に重なるブロックを上の行から左から順に調べ，
+    // 最初に見つかったものが中央の列にある場合は補正しない
+    fn can_kick(&self, mino: &Mino, new_angle: Angle, blocks: &Blocks) -> bool {
+        if !matches!(mino.shape, Shape::J | Shape::L | Shape::T) {
+            return true;
+        }
+
+        self.blocks(mino.shape, new_angle)
+            .iter()
+            .filter(|&&pos| {
+                blocks
+                    .get(pos + mino.pos)
+                    .is_none_or(|block| block.is_filled())
+            })
+            .min_by_key(|pos| (-pos.y, pos.x))
+            .is_none_or(|pos| pos.x != 1)
+    }
+}
+
+type MinoShapes = [[Position; 4]; 4];
+
+static I_SHAPES: MinoShapes = [
+    pos![(0, 2), (1, 2), (2, 2), (3, 2)],
+    pos![(2, 3), (2, 2), (2, 1), (2, 0)],
+    pos![(0, 2), (1, 2), (2, 2), (3, 2)],
+    pos![(2, 3), (2, 2), (2, 1), (2, 0)],
+];
+static J_SHAPES: MinoShapes = [
+    pos![(0, 1), (1, 1), (2, 1), (2, 0)],
+    pos![(1, 2), (1, 1), (0, 0), (1, 0)],
+    pos![(0, 1), (0, 0), (1, 0), (2, 0)],
+    pos![(1, 2), (2, 2), (1, 1), (1, 0)],
+];
+static L_SHAPES: MinoShapes = [
+    pos![(0, 1), (1, 1), (2, 1), (0, 0)],
+    pos![(0, 2), (1, 2), (1, 1), (1, 0)],
+    pos![(2, 1), (0, 0), (1, 0), (2, 0)],
+    pos![(1, 2), (1, 1), (1, 0), (2, 0)],
+];
+static O_SHAPES: MinoShapes = [
+    pos![(0, 1), (1, 1), (0, 0), (1, 0)],
+    pos![(0, 1), (1, 1), (0, 0), (1, 0)],
+    pos![(0, 1), (1, 1), (0, 0), (1, 0)],
+    pos![(0, 1), (1, 1), (0, 0), (1, 0)],
+];
+static S_SHAPES: MinoShapes = [
+    pos![(1, 1), (2, 1), (0, 0), (1, 0)],
+    pos![(0, 2), (0, 1), (1, 1), (1, 0)],
+    pos![(1, 1), (2, 1), (0, 0), (1, 0)],
+    pos![(0, 2), (0, 1), (1, 1), (1, 0)],
+];
+static T_SHAPES: MinoShapes = [
+    pos![(0, 1), (1, 1), (2, 1), (1, 0)],
+    pos![(1, 2), (0, 1), (1, 1), (1, 0)],
+    pos![(1, 1), (0, 0), (1, 0), (2, 0)],
+    pos![(1, 2), (1, 1), (2, 1), (1, 0)],
+];
+static Z_SHAPES: MinoShapes = [
+    pos![(0, 1), (1, 1), (1, 0), (2, 0)],
+    pos![(2, 2), (1, 1), (2, 1), (1, 0)],
+    pos![(0, 1), (1, 1), (1, 0), (2, 0)],
+    pos![(2, 2), (1, 1), (2, 1), (1, 0)],
+];
+
+static ARS_DELTAS: [Position; 3] = pos![(0, 0), (1, 0), (-1, 0)];
+static ARS_DELTAS_I: [Position; 1] = pos![(0, 0)];
@@ -0,0 +1,57 @@
+pub mod ars;
+pub mod nrs;
+pub mod srs;
+
+use self::{ars::Ars, nrs::Nrs, srs::Srs};
+use crate::{
+    field::blocks::Blocks,
+    mino::{shape::Shape, Angle, Mino},
+    position::Position,
+};
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+
+pub trait RotationSystem: Sync {
+    // 出現時の向き
+    fn spawn_angle(&self, _shape: Shape) -> Angle {
+        Angle::default()
+    }
+
+    // ミノの左下を原点とした，各向きでのブロックの位置
+    fn blocks(&self, shape: Shape, angle: Angle) -> &'static [Position];
+
+    // 回転補正の候補．先頭から順に試し，最初に置ける位置に移動する
+    fn kicks(&self, shape: Shape, angle: Angle, new_angle: Angle) -> &'static [Position];
+
+    // 回転補正を行ってよいか．falseの場合は補正なしの回転のみ試す
+    fn can_kick(&self, _mino: &Mino, _new_angle: Angle, _blocks: &Blocks) -> bool {
+        true
+    }
+
+    fn spawn_blocks(&self, shape: Shape) -> &'static [Position] {
+        self.blocks(shape, self.spawn_angle(shape))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize, ValueEnum)]
+pub enum RotationSystemKind {
+    #[default]
+    Srs,
+    Ars,
+    Nrs,
+}
+
+impl RotationSystemKind {
+    pub fn get(self) -> &'static dyn RotationSystem {
+        match self {
+            Self::Srs => &Srs,
+            Self::Ars => &Ars,
+            Self::Nrs => &Nrs,
+        }
+    }
+}
+
+// ブロックの位置の中で最も下の行
+pub fn bottom(blocks: &[Position]) -> i8 {
+    blocks.iter().map(|pos| pos.y).min().unwrap_or_default()
+}
@@ -0,0 +1,76 @@
+use super::RotationSystem;
+use crate::{
+    mino::{shape::Shape, Angle},
+    pos,
+    position::Position,
+};
+
+// ファミコン版テトリスの回転．回転補正は行わない
+pub struct Nrs;
+
+impl RotationSystem for Nrs {
+    fn blocks(&self, shape: Shape, angle: Angle) -> &'static [Position] {
+        let angle_idx: usize = angle.into();
+
+        match shape {
+            Shape::I => &I_SHAPES[angle_idx],
+            Shape::J => &J_SHAPES[angle_idx],
+            Shape::L => &L_SHAPES[angle_idx],
+            Shape::O => &O_SHAPES[angle_idx],
+            Shape::S => &S_SHAPES[angle_idx],
+            Shape::T => &T_SHAPES[angle_idx],
+            Shape::Z => &Z_SHAPES[angle_idx],
+        }
+    }
+
+    fn kicks(&self, _shape: Shape, _angle: Angle, _new_angle: Angle) -> &'static [Position] {
+        &NRS_DELTAS
+    }
+}
+
+type MinoShapes = [[Position; 4]; 4];
+
+static I_SHAPES: MinoShapes = [
+    pos![(0, 1), (1, 1), (2, 1), (3, 1)],
+    pos![(2, 3), (2, 2), (2, 1), (2, 0)],
+    pos![(0, 1), (1, 1), (2, 1), (3, 1)],
+    pos![(2, 3), (2, 2), (2, 1), (2, 0)],
+];
+static J_SHAPES: MinoShapes = [
+    pos![(0, 1), (1, 1), (2, 1), (2, 0)],
+    pos![(1, 2), (1, 1), (0, 0), (1, 0)],
+    pos![(0, 2), (0, 1), (1, 1), (2, 1)],
+    pos![(1, 2), (2, 2), (1, 1), (1, 0)],
+];
+static L_SHAPES: MinoShapes = [
+    pos![(0, 1), (1, 1), (2, 1), (0, 0)],
+    pos![(0, 2), (1, 2), (1, 1), (1, 0)],
+    pos![(2, 2), (0, 1), (1, 1), (2, 1)],
+    pos![(1, 2), (1, 1), (1, 0), (2, 0)],
+];
+static O_SHAPES: MinoShapes = [
+    pos![(0, 1), (1, 1), (0, 0), (1, 0)],
+    pos![(0, 1), (1, 1), (0, 0), (1, 0)],
+    pos![(0, 1), (1, 1), (0, 0), (1, 0)],
+    pos![(0, 1), (1, 1), (0, 0), (1, 0)],
+];
+static S_SHAPES: MinoShapes = [
+    pos![(1, 1), (2, 1), (0, 0), (1, 0)],
+    pos![(1, 2), (1, 1), (2, 1), (2, 0)],
+    pos![(1, 1), (2, 1), (0, 0), (1, 0)],
+    pos![(1, 2), (1, 1), (2, 1), (2, 0)],
+];
+static T_SHAPES: MinoShapes = [
+    pos![(0, 1), (1, 1), (2, 1), (1, 0)],
+    pos![(1, 2), (0, 1), (1, 1), (1, 0)],
+    pos![(1, 2), (0, 1), (1, 1), (2, 1)],
+    pos![(1, 2), (1, 1), (2, 1), (1, 0)],
+];
+static Z_SHAPES: MinoShapes = [
+    pos![(0, 1), (1, 1), (1, 0), (2, 0)],
+    pos![(2, 2), (1, 1), (2, 1), (1, 0)],
+    pos![(0, 1), (1, 1), (1, 0), (2, 0)],
+    pos![(2, 2), (1, 1), (2, 1), (1, 0)],
+];
+
+static NRS_DELTAS: [Position; 1] = pos![(0, 0)];
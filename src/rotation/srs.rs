@@ -0,0 +1,203 @@
+use super::RotationSystem;
+use crate::{
+    mino::{shape::Shape, Angle},
+    pos,
+    position::Position,
+};
+
+// スーパーローテーションシステム
+pub struct Srs;
+
+impl RotationSystem for Srs {
+    fn blocks(&self, shape: Shape, angle: Angle) -> &'static [Position] {
+        let angle_idx: usize = angle.into();
+
+        match shape {
+            Shape::I => &I_SHAPES[angle_idx],
+            Shape::J => &J_SHAPES[angle_idx],
+            Shape::L => &L_SHAPES[angle_idx],
+            Shape::O => &O_SHAPES[angle_idx],
+            Shape::S => &S_SHAPES[angle_idx],
+            Shape::T => &T_SHAPES[angle_idx],
+            Shape::Z => &Z_SHAPES[angle_idx],
+        }
+    }
+
+    fn kicks(&self, shape: Shape, angle: Angle, new_angle: Angle) -> &'static [Position] {
+        get_srs_deltas(angle, new_angle, shape)
+    }
+}
+
+macro_rules! define_shape {
+    ($shape:expr; $(($x:expr, $y:expr)),*) => {
+        [
+            [$(pos!($x, $y)),*],
+            [$(pos!($y, 1 - ($x - ($shape.width() - 2)))),*],
+            [$(pos!(($shape.width() - 1) - $x, ($shape.width() - 1) - $y)),*],
+            [$(pos!(1 - ($y - ($shape.width() - 2)), $x)),*],
+        ]
+    };
+}
+
+type MinoShapes = [[Position; 4]; 4];
+
+static I_SHAPES: MinoShapes = define_shape!(Shape::I; (0, 2), (1, 2), (2, 2), (3, 2));
+static J_SHAPES: MinoShapes = define_shape!(Shape::J; (0, 2), (0, 1), (1, 1), (2, 1));
+static L_SHAPES: MinoShapes = define_shape!(Shape::L; (2, 2), (0, 1), (1, 1), (2, 1));
+static O_SHAPES: MinoShapes = define_shape!(Shape::O; (0, 1), (1, 1), (0, 0), (1, 0));
+static S_SHAPES: MinoShapes = define_shape!(Shape::S; (1, 2), (2, 2), (0, 1), (1, 1));
+static T_SHAPES: MinoShapes = define_shape!(Shape::T; (1, 2), (0, 1), (1, 1), (2, 1));
+static Z_SHAPES: MinoShapes = define_shape!(Shape::Z; (0, 2), (1, 2), (1, 1), (2, 1));
+
+fn get_srs_deltas(angle: Angle, new_angle: Angle, shape: Shape) -> &'static [Position] {
+    use Angle::*;
+
+    // 180度回転の補正はTETR.IOを参考にした独自のもの．90度回転はガイドラインのSRSのまま
+    match (angle, new_angle, shape) {
+        (Deg0, Deg180, Shape::I) => return &SRS_DELTAS_0_TO_180_I,
+        (Deg180, Deg0, Shape::I) => return &SRS_DELTAS_180_TO_0_I,
+        (Deg90, Deg270, Shape::I) => return &SRS_DELTAS_90_TO_270_I,
+        (Deg270, Deg90, Shape::I) => return &SRS_DELTAS_270_TO_90_I,
+        (Deg0, Deg180, _) => return &SRS_DELTAS_0_TO_180,
+        (Deg180, Deg0, _) => return &SRS_DELTAS_180_TO_0,
+        (Deg90, Deg270, _) => return &SRS_DELTAS_90_TO_270,
+        (Deg270, Deg90, _) => return &SRS_DELTAS_270_TO_90,
+        _ => {}
+    }
+
+    if shape != Shape::I {
+        match (angle, new_angle) {
+            (Deg0, Deg90) => &SRS_DELTAS_0_TO_90,
+            (Deg90, Deg0) => &SRS_DELTAS_90_TO_0,
+            (Deg90, Deg180) => &SRS_DELTAS_90_TO_180,
+            (Deg180, Deg90) => &SRS_DELTAS_180_TO_90,
+            (Deg180, Deg270) => &SRS_DELTAS_180_TO_270,
+            (Deg270, Deg180) => &SRS_DELTAS_270_TO_180,
+            (Deg270, Deg0) => &SRS_DELTAS_270_TO_0,
+            (Deg0, Deg270) => &SRS_DELTAS_0_TO_270,
+            (_, _) => unreachable!(),
+        }
+    } else {
+        match (angle, new_angle) {
+            (Deg0, Deg90) => &SRS_DELTAS_0_TO_90_I,
+            (Deg90, Deg0) => &SRS_DELTAS_90_TO_0_I,
+            (Deg90, Deg180) => &SRS_DELTAS_90_TO_180_I,
+            (Deg180, Deg90) => &SRS_DELTAS_180_TO_90_I,
+            (Deg180, Deg270) => &SRS_DELTAS_180_TO_270_I,
+            (Deg270, Deg180) => &SRS_DELTAS_270_TO_180_I,
+            (Deg270, Deg0) => &SRS_DELTAS_270_TO_0_I,
+            (Deg0, Deg270) => &SRS_DELTAS_0_TO_270_I,
+            (_, _) => unreachable!(),
+        }
+    }
+}
+
+type SRSDeltas = [Position; 5];
+
+static SRS_DELTAS_0_TO_90: SRSDeltas = pos![(0, 0), (-1, 0), (-1, 1), (0, -2), (-1, -2)];
+static SRS_DELTAS_90_TO_0: SRSDeltas = pos![(0, 0), (1, 0), (1, -1), (0, 2), (1, 2)];
+static SRS_DELTAS_90_TO_180: SRSDeltas = pos![(0, 0), (1, 0), (1, -1), (0, 2), (1, 2)];
+static SRS_DELTAS_180_TO_90: SRSDeltas = pos![(0, 0), (-1, 0), (-1, 1), (0, -2), (-1, -2)];
+static SRS_DELTAS_180_TO_270: SRSDeltas = pos![(0, 0), (1, 0), (1, 1), (0, -2), (1, -2)];
+static SRS_DELTAS_270_TO_180: SRSDeltas = pos![(0, 0), (-1, 0), (-1, -1), (0, 2), (-1, 2)];
+static SRS_DELTAS_270_TO_0: SRSDeltas = pos![(0, 0), (-1, 0), (-1, -1), (0, 2), (-1, 2)];
+static SRS_DELTAS_0_TO_270: SRSDeltas = pos![(0, 0), (1, 0), (1, 1), (0, -2), (1, -2)];
+
+static SRS_DELTAS_0_TO_90_I: SRSDeltas = pos![(0, 0), (-2, 0), (1, 0), (-2, -1), (1, 2)];
+static SRS_DELTAS_90_TO_0_I: SRSDeltas = pos![(0, 0), (2, 0), (-1, 0), (2, 1), (-1, -2)];
+static SRS_DELTAS_90_TO_180_I: SRSDeltas = pos![(0, 0), (-1, 0), (2, 0), (-1, 2), (2, -1)];
+
+static SRS_DELTAS_180_TO_90_I: SRSDeltas = pos![(0, 0), (1, 0), (-2, 0), (1, -2), (-2, 1)];
+static SRS_DELTAS_180_TO_270_I: SRSDeltas = pos![(0, 0), (2, 0), (-1, 0), (2, 1), (-1, -2)];
+static SRS_DELTAS_270_TO_180_I: SRSDeltas = pos![(0, 0), (-2, 0), (1, 0), (-2, -1), (1, 2)];
+static SRS_DELTAS_270_TO_0_I: SRSDeltas = pos![(0, 0), (1, 0), (-2, 0), (1, -2), (-2, 1)];
+static SRS_DELTAS_0_TO_270_I: SRSDeltas = pos![(0, 0), (-1, 0), (2, 0), (-1, 2), (2, -1)];
+
+type SRSHalfDeltas = [Position; 6];
+
+static SRS_DELTAS_0_TO_180: SRSHalfDeltas = pos![(0, 0), (0, 1), (1, 1), (-1, 1), (1, 0), (-1, 0)];
+static SRS_DELTAS_180_TO_0: SRSHalfDeltas =
+    pos![(0, 0), (0, -1), (-1, -1), (1, -1), (-1, 0), (1, 0)];
+static SRS_DELTAS_90_TO_270: SRSHalfDeltas = pos![(0, 0), (1, 0), (1, 2), (1, 1), (0, 2), (0, 1)];
+static SRS_DELTAS_270_TO_90: SRSHalfDeltas =
+    pos![(0, 0), (-1, 0), (-1, 2), (-1, 1), (0, 2), (0, 1)];
+
+// Iミノは回転の中心がブロックの間にあるため，補正なしだと1マスずれる
+// 他のミノの補正を，同じ行や列に留まる位置を基準にずらしたもの
+static SRS_DELTAS_0_TO_180_I: SRSHalfDeltas =
+    pos![(0, 1), (0, 2), (1, 2), (-1, 2), (1, 1), (-1, 1)];
+static SRS_DELTAS_180_TO_0_I: SRSHalfDeltas =
+    pos![(0, -1), (0, -2), (-1, -2), (1, -2), (-1, -1), (1, -1)];
+static SRS_DELTAS_90_TO_270_I: SRSHalfDeltas = pos![(1, 0), (2, 0), (2, 2), (2, 1), (1, 2), (1, 1)];
+static SRS_DELTAS_270_TO_90_I: SRSHalfDeltas =
+    pos![(-1, 0), (-2, 0), (-2, 2), (-2, 1), (-1, 2), (-1, 1)];
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        field::{block::Block, blocks::Blocks},
+        mino::{shape::Shape, Angle, Mino},
+        movement::Rotation,
+        pos,
+        rotation::RotationSystemKind,
+    };
+
+    fn i_mino(angle: Angle, x: i8, y: i8) -> Mino {
+        Mino {
+            pos: pos!(x, y),
+            angle,
+            shape: Shape::I,
+            rotation_system: RotationSystemKind::Srs,
+        }
+    }
+
+    #[test]
+    fn i_half_rotation_kicks_along_right_wall() {
+        let mut blocks = Blocks::default();
+        for y in 0..4 {
+            *blocks.get_mut(pos!(8, y)).unwrap() = Block::Garbage;
+        }
+        // 右の壁に沿った縦向きのIミノ
+        let mino = i_mino(Angle::Deg90, 7, 0);
+        assert!(mino.positions().all(|pos| pos.x == 9));
+
+        let (rotated, delta) = mino.rotated(Rotation::Half, &blocks).unwrap();
+        assert_eq!(rotated.angle, Angle::Deg270);
+        assert_eq!(delta, pos!(1, 0));
+        assert!(rotated.positions().all(|pos| pos.x == 9));
+    }
+
+    #[test]
+    fn i_half_rotation_kicks_up_from_floor() {
+        let blocks = Blocks::default();
+        // 床に接した横向きのIミノ
+        let mino = i_mino(Angle::Deg0, 3, -2);
+        assert!(mino.positions().all(|pos| pos.y == 0));
+
+        let (rotated, delta) = mino.rotated(Rotation::Half, &blocks).unwrap();
+        assert_eq!(rotated.angle, Angle::Deg180);
+        assert_eq!(delta, pos!(0, 1));
+        assert!(rotated.positions().all(|pos| pos.y == 0));
+    }
+
+    #[test]
+    fn i_half_rotation_stays_in_place() {
+        let blocks = Blocks::default();
+        for (angle, new_angle) in [
+            (Angle::Deg0, Angle::Deg180),
+            (Angle::Deg180, Angle::Deg0),
+            (Angle::Deg90, Angle::Deg270),
+            (Angle::Deg270, Angle::Deg90),
+        ] {
+            let mino = i_mino(angle, 3, 5);
+            let (rotated, _) = mino.rotated(Rotation::Half, &blocks).unwrap();
+            assert_eq!(rotated.angle, new_angle);
+
+            let mut before = mino.positions().collect::<Vec<_>>();
+            let mut after = rotated.positions().collect::<Vec<_>>();
+            before.sort_by_key(|pos| (pos.x, pos.y));
+            after.sort_by_key(|pos| (pos.x, pos.y));
+            assert_eq!(before, after, "{angle:?}");
+        }
+    }
+}
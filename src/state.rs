@@ -1,5 +1,6 @@
 use crate::{
     field::{local::LocalField, Field},
+    game::TopOut,
     net::{broadcast_state, PlayerId, PlayerState, Players, Socket},
 };
 use bevy::prelude::*;
@@ -9,12 +10,17 @@ use if_chain::if_chain;
 pub enum AppState {
     #[default]
     MatchMaking,
+    // 1人用のモードを始める前
+    Countdown,
     Playing,
+    // 試合やモードが終わった後
+    // 終わる条件(ライン数，制限時間，おじゃま行を掘り切るなど)はモードごとのシステムが判定し，ここでは区別しない
+    // 条件ごとに状態を分けると，versusと全てのモードで共有している表示や入力のシステムの実行条件が増えるため
     Finished,
 }
 
 #[derive(Event)]
-pub struct GameOverEvent;
+pub struct GameOverEvent(pub TopOut);
 
 #[derive(Event)]
 pub struct StateChangeEvent {
@@ -25,19 +31,22 @@ pub struct StateChangeEvent {
 pub fn handle_gameover(
     mut events: EventReader<GameOverEvent>,
     mut state: ResMut<NextState<AppState>>,
-    mut socket: ResMut<Socket>,
-    players: Res<Players>,
+    mut socket: Option<ResMut<Socket>>,
+    players: Option<Res<Players>>,
     mut field_query: Query<&mut Field, With<LocalField>>,
 ) {
-    if events.read().next().is_none() {
+    let Some(&GameOverEvent(top_out)) = events.read().next() else {
         return;
-    }
+    };
     let Ok(mut field) = field_query.get_single_mut() else {
         return;
     };
 
-    field.player.state = PlayerState::GameOver;
-    broadcast_state(&mut socket, &players, PlayerState::GameOver);
+    field.player.state = PlayerState::GameOver(top_out);
+    // 通信している場合のみ他のプレイヤーに知らせる
+    if let (Some(socket), Some(players)) = (socket.as_deref_mut(), players.as_deref()) {
+        broadcast_state(socket, players, field.player.state);
+    }
 
     state.set(AppState::Finished);
 }
@@ -45,11 +54,16 @@ pub fn handle_gameover(
 pub fn handle_state_change(
     mut events: EventReader<StateChangeEvent>,
     mut state: ResMut<NextState<AppState>>,
-    mut socket: ResMut<Socket>,
-    mut players: ResMut<Players>,
+    mut socket: Option<ResMut<Socket>>,
+    players: Option<ResMut<Players>>,
     mut field_query: Query<&mut Field, Without<LocalField>>,
     mut my_field_query: Query<&mut Field, With<LocalField>>,
 ) {
+    // 通信していない場合は他のプレイヤーがいない
+    let Some(mut players) = players else {
+        return;
+    };
+
     for event in events.read() {
         if_chain! {
             if let Some(mut field) = field_query.iter_mut().find(|field| field.player.id == event.player_id);
@@ -63,14 +77,16 @@ pub fn handle_state_change(
         if players
             .0
             .iter()
-            .all(|player| player.state == PlayerState::GameOver)
+            .all(|player| matches!(player.state, PlayerState::GameOver(_)))
         {
             let Ok(mut my_field) = my_field_query.get_single_mut() else {
                 return;
             };
 
             my_field.player.state = PlayerState::Win;
-            broadcast_state(&mut socket, &players, PlayerState::Win);
+            if let Some(socket) = socket.as_deref_mut() {
+                broadcast_state(socket, &players, PlayerState::Win);
+            }
 
             state.set(AppState::Finished);
         }